@@ -0,0 +1,167 @@
+use macroquad::audio::{load_sound, play_sound, set_sound_volume, PlaySoundParams, Sound};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+const MUSIC_PATH: &str = "sounds/music.ogg";
+const SETTINGS_FILE: &str = "audio.json";
+const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Flip,
+    Match,
+    Mismatch,
+    Win,
+}
+
+impl Sfx {
+    const ALL: [Sfx; 4] = [Sfx::Flip, Sfx::Match, Sfx::Mismatch, Sfx::Win];
+
+    fn path(self) -> &'static str {
+        match self {
+            Sfx::Flip => "sounds/flip.ogg",
+            Sfx::Match => "sounds/match.ogg",
+            Sfx::Mismatch => "sounds/mismatch.ogg",
+            Sfx::Win => "sounds/win.ogg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Channel {
+    Master,
+    Sfx,
+    Music,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Volume {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+    pub muted: bool,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Volume {
+            master: 0.8,
+            sfx: 1.0,
+            music: 0.5,
+            muted: false,
+        }
+    }
+}
+
+impl Volume {
+    fn load() -> Self {
+        fs::read_to_string(SETTINGS_FILE)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn effective(&self, channel: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master * channel
+        }
+    }
+}
+
+/// Sound effects and looping music. Any sound file that is missing or
+/// cannot be decoded is reported once and then simply stays silent.
+pub struct Audio {
+    sfx: HashMap<Sfx, Sound>,
+    music: Option<Sound>,
+    pub volume: Volume,
+}
+
+impl Audio {
+    pub async fn load() -> Self {
+        let mut sfx = HashMap::with_capacity(Sfx::ALL.len());
+        for s in Sfx::ALL {
+            if let Some(sound) = try_load(s.path()).await {
+                sfx.insert(s, sound);
+            }
+        }
+
+        Audio {
+            sfx,
+            music: try_load(MUSIC_PATH).await,
+            volume: Volume::load(),
+        }
+    }
+
+    pub fn play(&self, sfx: Sfx) {
+        let volume = self.volume.effective(self.volume.sfx);
+        if volume <= 0.0 {
+            return;
+        }
+        if let Some(sound) = self.sfx.get(&sfx) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume,
+                },
+            );
+        }
+    }
+
+    pub fn start_music(&self) {
+        if let Some(music) = &self.music {
+            play_sound(
+                music,
+                PlaySoundParams {
+                    looped: true,
+                    volume: self.volume.effective(self.volume.music),
+                },
+            );
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.volume.muted = !self.volume.muted;
+        self.apply();
+    }
+
+    /// Raises (`steps > 0`) or lowers a channel, keeping it within 0..=1.
+    pub fn change(&mut self, channel: Channel, steps: i32) {
+        let level = match channel {
+            Channel::Master => &mut self.volume.master,
+            Channel::Sfx => &mut self.volume.sfx,
+            Channel::Music => &mut self.volume.music,
+        };
+        *level = (*level + steps as f32 * VOLUME_STEP).clamp(0.0, 1.0);
+        self.apply();
+    }
+
+    pub fn store(&self) {
+        let stored = serde_json::to_string(&self.volume)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| fs::write(SETTINGS_FILE, raw).map_err(|e| e.to_string()));
+        if let Err(e) = stored {
+            eprintln!("could not save audio settings, {}", e);
+        }
+    }
+
+    /// Music is already playing, so volume changes must be pushed to it.
+    fn apply(&self) {
+        if let Some(music) = &self.music {
+            set_sound_volume(music, self.volume.effective(self.volume.music));
+        }
+    }
+}
+
+async fn try_load(path: &str) -> Option<Sound> {
+    match load_sound(path).await {
+        Ok(sound) => Some(sound),
+        Err(e) => {
+            eprintln!("playing without {}, {}", path, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use macroquad::rand::{ChooseRandom, RandGenerator};
+
+/// Crockford's base32, no I, L, O or U to mix up when reading a code aloud.
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+pub const CODE_LEN: usize = 6;
+/// Every seed below this fits in a deal code.
+pub const SEED_LIMIT: u64 = 1 << (5 * CODE_LEN);
+
+/// Picks `pairs` images and shuffles both cards of each pair.
+/// The same images and seed always give the same deal.
+pub fn deal(images: &[String], pairs: usize, seed: u64) -> Vec<String> {
+    let rng = RandGenerator::new();
+    rng.srand(seed);
+
+    let mut chosen = images.to_vec();
+    chosen.shuffle_with_state(&rng);
+    chosen.truncate(pairs);
+
+    let mut ids: Vec<String> = chosen.iter().chain(chosen.iter()).cloned().collect();
+    ids.shuffle_with_state(&rng);
+    ids
+}
+
+/// Short code for a seed, e.g. `4F9K2Z`.
+pub fn to_code(seed: u64) -> String {
+    (0..CODE_LEN)
+        .rev()
+        .map(|i| CODE_ALPHABET[((seed >> (5 * i)) & 31) as usize] as char)
+        .collect()
+}
+
+/// Seed for a typed code, lenient with case and look-alike letters.
+pub fn from_code(code: &str) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != CODE_LEN {
+        return None;
+    }
+
+    code.chars().try_fold(0, |seed, c| {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let digit = CODE_ALPHABET.iter().position(|a| *a as char == c)?;
+        Some((seed << 5) | digit as u64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_round_trip() {
+        for seed in [0, 1, 31, 32, 123_456, SEED_LIMIT - 1] {
+            let code = to_code(seed);
+            assert_eq!(code.len(), CODE_LEN);
+            assert_eq!(from_code(&code), Some(seed));
+            assert_eq!(from_code(&code.to_lowercase()), Some(seed));
+        }
+        assert_eq!(from_code("oooool"), from_code("000001"));
+        assert_eq!(from_code("12345"), None);
+        assert_eq!(from_code("ABCDE!"), None);
+    }
+}
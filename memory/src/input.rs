@@ -0,0 +1,78 @@
+use macroquad::prelude::*;
+
+/// Device independent navigation over the card grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Flip,
+}
+
+const KEY_BINDINGS: [(KeyCode, Nav); 7] = [
+    (KeyCode::Up, Nav::Up),
+    (KeyCode::Down, Nav::Down),
+    (KeyCode::Left, Nav::Left),
+    (KeyCode::Right, Nav::Right),
+    (KeyCode::Enter, Nav::Flip),
+    (KeyCode::KpEnter, Nav::Flip),
+    (KeyCode::Space, Nav::Flip),
+];
+
+/// Collects navigation from the keyboard and, when built with the
+/// `gamepad` feature, from the D-pad and the south (A) button.
+pub struct NavInput {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl NavInput {
+    pub fn new() -> Self {
+        NavInput {
+            #[cfg(feature = "gamepad")]
+            gilrs: gilrs::Gilrs::new().ok(),
+        }
+    }
+
+    /// Navigation requested since the last frame, keyboard first.
+    /// Must be called once per frame so gamepad events do not pile up.
+    pub fn poll(&mut self) -> Vec<Nav> {
+        KEY_BINDINGS
+            .iter()
+            .filter(|(key, _)| is_key_pressed(*key))
+            .map(|(_, nav)| *nav)
+            .chain(self.gamepad_navs())
+            .collect()
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_navs(&mut self) -> Vec<Nav> {
+        let mut navs = vec![];
+        if let Some(gilrs) = self.gilrs.as_mut() {
+            while let Some(gilrs::Event { event, .. }) = gilrs.next_event() {
+                if let gilrs::EventType::ButtonPressed(button, _) = event {
+                    navs.extend(button_nav(button));
+                }
+            }
+        }
+        navs
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn gamepad_navs(&mut self) -> Vec<Nav> {
+        vec![]
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn button_nav(button: gilrs::Button) -> Option<Nav> {
+    match button {
+        gilrs::Button::DPadUp => Some(Nav::Up),
+        gilrs::Button::DPadDown => Some(Nav::Down),
+        gilrs::Button::DPadLeft => Some(Nav::Left),
+        gilrs::Button::DPadRight => Some(Nav::Right),
+        gilrs::Button::South => Some(Nav::Flip),
+        _ => None,
+    }
+}
@@ -1,6 +1,17 @@
+mod audio;
+mod deal;
+mod input;
+mod menu;
+mod save;
+mod sequence;
+mod whats_new;
+
+use audio::{Audio, Channel, Sfx};
+use input::{Nav, NavInput};
 use macroquad::prelude::*;
-use macroquad::rand::ChooseRandom;
-use std::collections::HashSet;
+use menu::Mode;
+use save::SaveGame;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
 const SCREEN_WIDTH: u32 = 1280;
@@ -10,57 +21,208 @@ const SCREEN_OFFSET: f32 = 15.0;
 const CARD_WIDTH: f32 = 300.0;
 const CARD_HEIGHT: f32 = 180.0;
 const MAX_CARDS: usize = 9;
+const FOCUS_THICKNESS: f32 = 5.0;
+const VOLUME_SHOWN_FOR: f64 = 2.0;
+
+const VOLUME_KEYS: [(KeyCode, Channel, i32); 8] = [
+    (KeyCode::Minus, Channel::Master, -1),
+    (KeyCode::Equal, Channel::Master, 1),
+    (KeyCode::KpSubtract, Channel::Master, -1),
+    (KeyCode::KpAdd, Channel::Master, 1),
+    (KeyCode::LeftBracket, Channel::Sfx, -1),
+    (KeyCode::RightBracket, Channel::Sfx, 1),
+    (KeyCode::Comma, Channel::Music, -1),
+    (KeyCode::Period, Channel::Music, 1),
+];
+
+type Textures = HashMap<String, Texture2D>;
+
+/// How a mode was left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Menu,
+    Quit,
+}
+
+/// Everything the modes have in common, loaded once at startup.
+struct Shared {
+    images: Vec<String>,
+    textures: Textures,
+    audio: Audio,
+    nav_input: NavInput,
+    volume_shown_until: f64,
+}
+
+impl Shared {
+    async fn load() -> Self {
+        Shared {
+            images: image_paths(),
+            textures: HashMap::with_capacity(MAX_CARDS + 1),
+            // missing sounds stay silent
+            audio: Audio::load().await,
+            nav_input: NavInput::new(),
+            volume_shown_until: 0.0,
+        }
+    }
+
+    /// Navigation for this frame. The volume keys are handled on the way,
+    /// unless the player is `typing`.
+    fn poll(&mut self, typing: bool) -> Vec<Nav> {
+        if !typing && volume_keys(&mut self.audio) {
+            self.volume_shown_until = get_time() + VOLUME_SHOWN_FOR;
+        }
+        self.nav_input.poll()
+    }
+
+    fn draw_overlays(&self) {
+        if get_time() < self.volume_shown_until {
+            draw_volume(&self.audio);
+        }
+    }
+}
 
 #[macroquad::main("memory")]
 async fn main() {
     rand::srand(miniquad::date::now() as u64);
     //set_window_size(SCREEN_WIDTH, SCREEN_HEIGHT);
+    prevent_quit();
+
+    let mut shared = Shared::load().await;
+    shared.audio.start_music();
+
+    loop {
+        let saved = SaveGame::load().unwrap_or_else(|e| {
+            eprintln!("ignoring saved game, {}", e);
+            None
+        });
+
+        let exit = match menu::select_mode(&mut shared, saved.is_some()).await {
+            Some(Mode::Continue) => classic(&mut shared, saved).await,
+            Some(Mode::Classic) => classic(&mut shared, None).await,
+            Some(Mode::Sequence) => sequence::play(&mut shared).await,
+            Some(Mode::WhatsNew) => whats_new::play(&mut shared).await,
+            None => Exit::Quit,
+        };
+
+        if exit == Exit::Quit {
+            shared.audio.store();
+            break;
+        }
+    }
+}
 
+/// Classic pairs, continuing `save` when there is one.
+async fn classic(shared: &mut Shared, save: Option<SaveGame>) -> Exit {
     // control
     let mut found_pairs: HashSet<String> = HashSet::with_capacity(MAX_CARDS);
     let mut current_opens: Vec<Card> = Vec::with_capacity(2);
     let mut game_over = false;
+    let mut focus: usize = 0;
+    let mut moves: u32 = 0;
+    let mut elapsed: f64 = 0.0;
+    let mut code_entry: Option<String> = None;
 
-    // loading images
-    let mut images = load_images().await;
-
-    // create cards
-    let mut cards: Vec<Card> = make_cards(images);
+    // create cards, continuing the last game if asked to
+    let mut seed = 0;
+    let mut next_seed = Some(rand::gen_range(0, deal::SEED_LIMIT));
+    let mut cards: Vec<Card> = vec![];
+    if let Some(save) = save {
+        let saved_cards = layout_cards(save.cards);
+        let complete = saved_cards.len() == (MAX_CARDS + 1) * 2;
+        match load_textures(&mut shared.textures, &saved_cards).await {
+            Ok(()) if complete => {
+                seed = save.seed;
+                next_seed = None;
+                cards = saved_cards;
+                found_pairs.extend(save.found_pairs);
+                current_opens.extend(save.open_cards.iter().map(|i| cards[*i].clone()));
+                moves = save.moves;
+                elapsed = save.elapsed;
+            }
+            _ => eprintln!("ignoring saved game, its cards cannot be restored"),
+        }
+    }
+    SaveGame::discard();
 
     loop {
-        if game_over && is_key_pressed(KeyCode::Space) {
-            images = load_images().await;
+        if let Some(s) = next_seed.take() {
+            seed = s;
+            cards = make_cards(&shared.images, seed);
+            load_textures(&mut shared.textures, &cards).await.unwrap();
             found_pairs.clear();
             current_opens.clear();
             game_over = false;
-            cards.clear();
-            cards = make_cards(images);
+            focus = 0;
+            moves = 0;
+            elapsed = 0.0;
+        }
+
+        let quit = is_quit_requested();
+        if quit || (code_entry.is_none() && is_key_pressed(KeyCode::Escape)) {
+            if !game_over {
+                save_game(seed, &cards, &found_pairs, &current_opens, moves, elapsed);
+            }
+            return if quit { Exit::Quit } else { Exit::Menu };
         }
 
-        // check click
-        if !game_over {
-            if is_mouse_button_pressed(MouseButton::Left) {
-                let (x_mouse, y_mouse) = mouse_position();
-                let clicked = cards
-                    .clone()
+        let navs = shared.poll(code_entry.is_some());
+
+        if let Some(code) = code_entry.as_mut() {
+            // chars come out last typed first
+            let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+            typed.reverse();
+            code.extend(
+                typed
                     .into_iter()
-                    .find(|c| c.clicked_at(x_mouse, y_mouse));
+                    .filter(char::is_ascii_alphanumeric)
+                    .map(|c| c.to_ascii_uppercase()),
+            );
+            code.truncate(deal::CODE_LEN);
+
+            if is_key_pressed(KeyCode::Backspace) {
+                code.pop();
+            }
+            if is_key_pressed(KeyCode::Escape) {
+                code_entry = None;
+            } else if is_key_pressed(KeyCode::Enter) {
+                if let Some(s) = deal::from_code(code) {
+                    next_seed = Some(s);
+                    code_entry = None;
+                }
+            }
+        } else if is_key_pressed(KeyCode::D) {
+            clear_input_queue();
+            code_entry = Some(String::new());
+        } else if game_over && navs.contains(&Nav::Flip) {
+            next_seed = Some(rand::gen_range(0, deal::SEED_LIMIT));
+        } else if !game_over {
+            elapsed += get_frame_time() as f64;
 
-                if clicked.is_some() {
-                    if current_opens.len() == 2 {
-                        current_opens.clear()
+            if let Some(i) = pick_card(&cards, &mut focus, navs) {
+                if current_opens.len() == 2 {
+                    current_opens.clear()
+                }
+                current_opens.push(cards[i].clone());
+                shared.audio.play(Sfx::Flip);
+                if current_opens.len() == 2 {
+                    moves += 1;
+                    if current_opens[0].is_other_pair(&current_opens[1]) {
+                        shared.audio.play(Sfx::Match);
+                    } else {
+                        shared.audio.play(Sfx::Mismatch);
                     }
-                    let to_add = clicked.unwrap().clone();
-                    current_opens.push(to_add);
                 }
             }
         }
 
         // draw
-        for c in &cards {
-            let is_open = current_opens.iter().find(|co| co.is_equal(c)).is_some();
+        for (i, c) in cards.iter().enumerate() {
+            let is_open = current_opens.iter().any(|co| co.is_equal(c));
             let is_found = found_pairs.contains(&c.id);
-            c.draw(is_open, is_found);
+            c.draw(&shared.textures[&c.id], is_open, is_found);
+            if i == focus && !game_over {
+                c.draw_focus();
+            }
         }
 
         if !game_over && current_opens.len() == 2 {
@@ -73,6 +235,9 @@ async fn main() {
         }
 
         if found_pairs.len() == MAX_CARDS + 1 {
+            if !game_over {
+                shared.audio.play(Sfx::Win);
+            }
             game_over = true;
             draw_text(
                 "YOU WIN!!!",
@@ -81,34 +246,156 @@ async fn main() {
                 (SCREEN_WIDTH / 7) as f32,
                 GREEN,
             );
+            draw_text(
+                &format!("{} moves in {:.0} seconds", moves, elapsed),
+                (SCREEN_WIDTH / 7) as f32,
+                (SCREEN_HEIGHT / 2) as f32 + SCREEN_OFFSET * 4.0,
+                (SCREEN_WIDTH / 20) as f32,
+                DARKGREEN,
+            );
         }
 
+        draw_deal_code(seed, code_entry.as_deref());
+        shared.draw_overlays();
+
         next_frame().await;
     }
 }
 
-fn make_cards(images: Vec<(String, Texture2D)>) -> Vec<Card> {
-    let mut cards: Vec<Card> = Vec::with_capacity(MAX_CARDS * 2);
+/// Card chosen this frame by a click or a flip, moving the focus along.
+fn pick_card(cards: &[Card], focus: &mut usize, navs: Vec<Nav>) -> Option<usize> {
+    // check click
+    let mut picked = None;
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let (x_mouse, y_mouse) = mouse_position();
+        if let Some(clicked) = cards.iter().position(|c| c.clicked_at(x_mouse, y_mouse)) {
+            *focus = clicked;
+            picked = Some(clicked);
+        }
+    }
+
+    // check keyboard / gamepad
+    for nav in navs {
+        match nav {
+            Nav::Flip => picked = Some(*focus),
+            _ => *focus = move_focus(cards, *focus, nav),
+        }
+    }
+    picked
+}
+
+/// Applies the mute and volume keys, true when any of them was pressed.
+fn volume_keys(audio: &mut Audio) -> bool {
+    let mut pressed = false;
+    if is_key_pressed(KeyCode::M) {
+        audio.toggle_mute();
+        pressed = true;
+    }
+    for (key, channel, steps) in VOLUME_KEYS {
+        if is_key_pressed(key) {
+            audio.change(channel, steps);
+            pressed = true;
+        }
+    }
+    pressed
+}
+
+fn draw_volume(audio: &Audio) {
+    let v = &audio.volume;
+    let text = if v.muted {
+        "Muted (M)".to_string()
+    } else {
+        format!(
+            "Volume {:.0}%   SFX {:.0}%   Music {:.0}%",
+            v.master * 100.0,
+            v.sfx * 100.0,
+            v.music * 100.0
+        )
+    };
+    let dimensions = measure_text(&text, None, 30, 1.0);
+    let x = screen_width() - dimensions.width - SCREEN_OFFSET;
+    draw_rectangle(x - 5.0, 0.0, dimensions.width + 10.0, 40.0, BLACK);
+    draw_text(&text, x, 30.0, 30.0, WHITE);
+}
+
+/// One line of help or score along the bottom of the screen.
+fn draw_status(text: &str) {
+    draw_text(
+        text,
+        SCREEN_OFFSET,
+        screen_height() - SCREEN_OFFSET,
+        30.0,
+        GRAY,
+    );
+}
+
+fn draw_deal_code(seed: u64, code_entry: Option<&str>) {
+    let y = screen_height() - SCREEN_OFFSET;
+    match code_entry {
+        Some(code) => {
+            draw_rectangle(0.0, y - 60.0, screen_width(), 60.0 + SCREEN_OFFSET, BLACK);
+            let typed = format!("{:_<width$}", code, width = deal::CODE_LEN);
+            draw_text(
+                &format!("Deal code: {}   (Enter to play, Esc to cancel)", typed),
+                SCREEN_OFFSET,
+                y,
+                40.0,
+                YELLOW,
+            );
+        }
+        None => {
+            let text = format!("Deal {}   (D to enter a code)", deal::to_code(seed));
+            draw_text(&text, SCREEN_OFFSET, y, 30.0, DARKGRAY);
+        }
+    }
+}
+
+fn save_game(
+    seed: u64,
+    cards: &[Card],
+    found_pairs: &HashSet<String>,
+    current_opens: &[Card],
+    moves: u32,
+    elapsed: f64,
+) {
+    let save = SaveGame::new(
+        seed,
+        cards.iter().map(|c| c.id.clone()).collect(),
+        found_pairs.iter().cloned().collect(),
+        current_opens
+            .iter()
+            .filter_map(|co| cards.iter().position(|c| c.is_equal(co)))
+            .collect(),
+        moves,
+        elapsed,
+    );
+    if let Err(e) = save.store() {
+        eprintln!("could not save the game, {}", e);
+    }
+}
+
+/// Deals and lays out the cards for `seed`, the same seed always gives the same board.
+fn make_cards(images: &[String], seed: u64) -> Vec<Card> {
+    layout_cards(deal::deal(images, MAX_CARDS + 1, seed))
+}
+
+fn layout_cards(ids: Vec<String>) -> Vec<Card> {
+    let mut cards: Vec<Card> = Vec::with_capacity(ids.len());
     let mut initial_x = SCREEN_OFFSET;
     let mut initial_y = SCREEN_OFFSET;
 
-    for (id, img) in images {
+    for id in ids {
         // reset if higher
         if (initial_x + SCREEN_OFFSET + CARD_WIDTH) >= (SCREEN_WIDTH as f32) {
             initial_y += CARD_HEIGHT + SCREEN_OFFSET;
             initial_x = SCREEN_OFFSET;
         }
 
-        let data = img.get_texture_data();
-        let card = Card {
-            id: id.clone(),
-            texture: img.clone(),
+        cards.push(Card {
+            id,
             x: initial_x,
             y: initial_y,
-            width: data.width as f32,
-            height: data.height as f32,
-        };
-        cards.push(card);
+        });
 
         // bump x coord
         initial_x += CARD_WIDTH + SCREEN_OFFSET;
@@ -116,42 +403,62 @@ fn make_cards(images: Vec<(String, Texture2D)>) -> Vec<Card> {
     cards
 }
 
-async fn load_images() -> Vec<(String, Texture2D)> {
-    let images_path: Vec<String> = fs::read_dir("images/round")
+/// Moves the focus one step over the grid laid out by `make_cards`.
+/// Going down into a shorter last row lands on its last card.
+fn move_focus(cards: &[Card], focus: usize, nav: Nav) -> usize {
+    if cards.is_empty() {
+        return 0;
+    }
+    let columns = cards.iter().take_while(|c| c.y == cards[0].y).count();
+    let last = cards.len() - 1;
+    let column = focus % columns;
+
+    match nav {
+        Nav::Left if column > 0 => focus - 1,
+        Nav::Right if column < columns - 1 && focus < last => focus + 1,
+        Nav::Up if focus >= columns => focus - columns,
+        Nav::Down if focus - column + columns <= last => (focus + columns).min(last),
+        _ => focus,
+    }
+}
+
+fn image_paths() -> Vec<String> {
+    let mut images_path: Vec<String> = fs::read_dir("images/round")
         .unwrap()
         .map(|r| r.unwrap().path())
         .map(|r| r.clone().to_str().unwrap().to_owned())
         .collect();
 
-    let mut images = Vec::with_capacity(MAX_CARDS);
-    for i in &images_path[0..=MAX_CARDS] {
-        let img = load_texture(i).await.unwrap();
-        images.push((i.clone(), img.clone()));
-        images.push((i.clone(), img.clone()));
-    }
+    // directory order differs between machines, deals must not
+    images_path.sort();
+    images_path
+}
 
-    images.shuffle();
-    images
+async fn load_textures(textures: &mut Textures, cards: &[Card]) -> Result<(), macroquad::Error> {
+    for c in cards {
+        if !textures.contains_key(&c.id) {
+            let texture = load_texture(&c.id).await?;
+            textures.insert(c.id.clone(), texture);
+        }
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct Card {
     id: String,
-    texture: Texture2D,
     x: f32,
     y: f32,
-    width: f32,
-    height: f32,
 }
 
 impl Card {
-    fn draw(self: &Self, is_open: bool, is_found: bool) {
+    fn draw(&self, texture: &Texture2D, is_open: bool, is_found: bool) {
         if is_open || is_found {
             let x_middle = self.x + CARD_WIDTH / 2.0;
             let y_middle = self.y + CARD_HEIGHT / 2.0;
 
-            let x_img = x_middle - (self.width / 2.0);
-            let y_img = y_middle - (self.height / 2.0);
+            let x_img = x_middle - (texture.width() / 2.0);
+            let y_img = y_middle - (texture.height() / 2.0);
 
             if is_found {
                 draw_rectangle(self.x, self.y, CARD_WIDTH, CARD_HEIGHT, PINK); // open
@@ -159,21 +466,32 @@ impl Card {
                 draw_rectangle(self.x, self.y, CARD_WIDTH, CARD_HEIGHT, SKYBLUE);
                 // open
             }
-            draw_texture(&self.texture, x_img, y_img, WHITE);
+            draw_texture(texture, x_img, y_img, WHITE);
         } else {
             draw_rectangle(self.x, self.y, CARD_WIDTH, CARD_HEIGHT, LIGHTGRAY); // closed
         }
     }
 
-    fn is_other_pair(self: &Self, other: &Card) -> bool {
+    fn draw_focus(&self) {
+        draw_rectangle_lines(
+            self.x - FOCUS_THICKNESS,
+            self.y - FOCUS_THICKNESS,
+            CARD_WIDTH + 2.0 * FOCUS_THICKNESS,
+            CARD_HEIGHT + 2.0 * FOCUS_THICKNESS,
+            FOCUS_THICKNESS * 2.0,
+            ORANGE,
+        );
+    }
+
+    fn is_other_pair(&self, other: &Card) -> bool {
         self.id == other.id && (self.x != other.x || self.y != other.y)
     }
 
-    fn is_equal(self: &Self, other: &Card) -> bool {
+    fn is_equal(&self, other: &Card) -> bool {
         self.id == other.id && self.x == other.x && self.y == other.y
     }
 
-    fn clicked_at(self: &Self, x_target: f32, y_target: f32) -> bool {
+    fn clicked_at(&self, x_target: f32, y_target: f32) -> bool {
         let mouse_rect = Rect {
             x: x_target,
             y: y_target,
@@ -189,3 +507,65 @@ impl Card {
         card_rect.intersect(mouse_rect).is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_cards() {
+        let images: Vec<String> = (0..30).map(|i| format!("images/round/{}.png", i)).collect();
+
+        let cards = make_cards(&images, 42);
+        assert_eq!(cards.len(), (MAX_CARDS + 1) * 2);
+        assert_eq!(cards, make_cards(&images, 42));
+        assert_ne!(cards, make_cards(&images, 43));
+    }
+
+    fn grid(count: usize) -> Vec<Card> {
+        layout_cards((0..count).map(|i| i.to_string()).collect())
+    }
+
+    #[test]
+    fn focus_in_a_shorter_last_row() {
+        // four to a row, so six cards leave two in the last one
+        let cards = grid(6);
+        assert_eq!(move_focus(&cards, 0, Nav::Down), 4);
+        assert_eq!(move_focus(&cards, 1, Nav::Down), 5);
+        assert_eq!(move_focus(&cards, 3, Nav::Down), 5);
+        assert_eq!(move_focus(&cards, 5, Nav::Right), 5);
+        assert_eq!(move_focus(&cards, 5, Nav::Down), 5);
+        assert_eq!(move_focus(&cards, 5, Nav::Up), 1);
+        assert_eq!(move_focus(&cards, 4, Nav::Up), 0);
+        assert_eq!(move_focus(&cards, 4, Nav::Left), 4);
+    }
+
+    #[test]
+    fn focus_stays_on_ragged_grids() {
+        for count in [5, 6, 7, 9, 10, 11] {
+            let cards = grid(count);
+            let columns = cards.iter().take_while(|c| c.y == cards[0].y).count();
+            let last = count - 1;
+            let last_row = last - last % columns;
+            assert_ne!(count % columns, 0, "{} cards", count);
+
+            for focus in 0..count {
+                let down = move_focus(&cards, focus, Nav::Down);
+                assert!(down <= last, "{} cards, down from {}", count, focus);
+                if focus < last_row && focus + columns > last {
+                    assert_eq!(down, last, "{} cards, down from {}", count, focus);
+                }
+                if focus >= last_row {
+                    assert_eq!(down, focus, "{} cards, down from {}", count, focus);
+                    assert_eq!(move_focus(&cards, focus, Nav::Up), focus - columns);
+                }
+            }
+            assert_eq!(
+                move_focus(&cards, last, Nav::Right),
+                last,
+                "{} cards",
+                count
+            );
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::input::Nav;
+use crate::{Shared, SCREEN_OFFSET};
+use macroquad::prelude::*;
+
+const FONT_SIZE: f32 = 50.0;
+const ITEM_HEIGHT: f32 = 70.0;
+const TOP: f32 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Continue,
+    Classic,
+    Sequence,
+    WhatsNew,
+}
+
+impl Mode {
+    fn title(self) -> &'static str {
+        match self {
+            Mode::Continue => "Continue pairs",
+            Mode::Classic => "Pairs",
+            Mode::Sequence => "Sequence: repeat the cards in order",
+            Mode::WhatsNew => "What's new: spot the changed card",
+        }
+    }
+}
+
+/// Lets the player pick a mode, `None` when the window is being closed.
+pub async fn select_mode(shared: &mut Shared, can_continue: bool) -> Option<Mode> {
+    let modes: Vec<Mode> = [
+        Mode::Continue,
+        Mode::Classic,
+        Mode::Sequence,
+        Mode::WhatsNew,
+    ]
+    .into_iter()
+    .filter(|m| can_continue || *m != Mode::Continue)
+    .collect();
+    let mut selected = 0;
+
+    loop {
+        if is_quit_requested() {
+            return None;
+        }
+
+        let mut chosen = None;
+        for nav in shared.poll(false) {
+            match nav {
+                Nav::Up => selected = (selected + modes.len() - 1) % modes.len(),
+                Nav::Down => selected = (selected + 1) % modes.len(),
+                Nav::Flip => chosen = Some(modes[selected]),
+                _ => {}
+            }
+        }
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (x_mouse, y_mouse) = mouse_position();
+            if let Some(i) =
+                (0..modes.len()).find(|i| item_rect(*i).contains(vec2(x_mouse, y_mouse)))
+            {
+                selected = i;
+                chosen = Some(modes[i]);
+            }
+        }
+
+        // draw
+        clear_background(BLACK);
+        draw_text(
+            "MEMORY",
+            SCREEN_OFFSET * 4.0,
+            TOP - ITEM_HEIGHT * 1.5,
+            FONT_SIZE * 1.5,
+            GOLD,
+        );
+        for (i, mode) in modes.iter().enumerate() {
+            let rect = item_rect(i);
+            let color = if i == selected { ORANGE } else { WHITE };
+            if i == selected {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 4.0, ORANGE);
+            }
+            draw_text(
+                mode.title(),
+                rect.x + SCREEN_OFFSET,
+                rect.y + FONT_SIZE,
+                FONT_SIZE,
+                color,
+            );
+        }
+        draw_text(
+            "Arrows and Enter to choose, Esc goes back to this menu",
+            SCREEN_OFFSET,
+            screen_height() - SCREEN_OFFSET,
+            30.0,
+            GRAY,
+        );
+        shared.draw_overlays();
+
+        next_frame().await;
+
+        // only now, so the key that chose the mode is not seen by it again
+        if chosen.is_some() {
+            return chosen;
+        }
+    }
+}
+
+fn item_rect(i: usize) -> Rect {
+    Rect {
+        x: SCREEN_OFFSET * 3.0,
+        y: TOP + i as f32 * ITEM_HEIGHT,
+        w: screen_width() - SCREEN_OFFSET * 6.0,
+        h: ITEM_HEIGHT - SCREEN_OFFSET,
+    }
+}
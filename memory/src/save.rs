@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io;
+
+const SAVE_FILE: &str = "memory.save";
+const SAVE_VERSION: u64 = 1;
+
+/// Everything needed to put an unfinished board back on screen.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u64,
+    pub seed: u64,
+    /// card ids in the order they were dealt
+    pub cards: Vec<String>,
+    pub found_pairs: Vec<String>,
+    /// positions in `cards` of the currently open cards
+    pub open_cards: Vec<usize>,
+    pub moves: u32,
+    pub elapsed: f64,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    Version(u64),
+    Board,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "cannot access save file: {}", e),
+            SaveError::Format(e) => write!(f, "malformed save file: {}", e),
+            SaveError::Version(v) => write!(f, "unsupported save version {}", v),
+            SaveError::Board => write!(f, "saved board does not match the card images"),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Format(e)
+    }
+}
+
+impl SaveGame {
+    pub fn new(
+        seed: u64,
+        cards: Vec<String>,
+        found_pairs: Vec<String>,
+        open_cards: Vec<usize>,
+        moves: u32,
+        elapsed: f64,
+    ) -> Self {
+        SaveGame {
+            version: SAVE_VERSION,
+            seed,
+            cards,
+            found_pairs,
+            open_cards,
+            moves,
+            elapsed,
+        }
+    }
+
+    /// Reads the save file, `Ok(None)` when there is nothing to continue.
+    pub fn load() -> Result<Option<SaveGame>, SaveError> {
+        let raw = match fs::read_to_string(SAVE_FILE) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let save = migrate(serde_json::from_str(&raw)?)?;
+
+        let board_ok = save.open_cards.iter().all(|i| *i < save.cards.len())
+            && save.found_pairs.iter().all(|id| save.cards.contains(id));
+        if !board_ok {
+            return Err(SaveError::Board);
+        }
+        Ok(Some(save))
+    }
+
+    pub fn store(&self) -> Result<(), SaveError> {
+        fs::write(SAVE_FILE, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn discard() {
+        if let Err(e) = fs::remove_file(SAVE_FILE) {
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("could not remove {}: {}", SAVE_FILE, e);
+            }
+        }
+    }
+}
+
+/// Brings an older save up to `SAVE_VERSION`, one version at a time.
+/// Saves from unknown (newer) versions are rejected.
+fn migrate(save: Value) -> Result<SaveGame, SaveError> {
+    let version = save.get("version").and_then(Value::as_u64).unwrap_or(0);
+    match version {
+        SAVE_VERSION => Ok(serde_json::from_value(save)?),
+        v => Err(SaveError::Version(v)),
+    }
+}
@@ -0,0 +1,113 @@
+use crate::audio::Sfx;
+use crate::input::Nav;
+use crate::{draw_status, layout_cards, load_textures, pick_card, Card, Exit, Shared};
+use macroquad::prelude::*;
+use macroquad::rand::ChooseRandom;
+
+const CARDS: usize = 8;
+const LIT_FOR: f64 = 0.6;
+const DARK_FOR: f64 = 0.25;
+const PAUSE_BEFORE_SHOW: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    /// the sequence lights up, starting at the given time
+    Show(f64),
+    /// the player repeats it, with this many cards already right
+    Repeat(usize),
+    Over,
+}
+
+/// Simon style: cards light up in a sequence that grows by one every
+/// round, the player repeats it. The score is the longest sequence repeated.
+pub async fn play(shared: &mut Shared) -> Exit {
+    let mut cards = deal_cards(shared).await;
+    let mut sequence = vec![rand::gen_range(0, CARDS)];
+    let mut phase = Phase::Show(get_time() + PAUSE_BEFORE_SHOW);
+    let mut focus: usize = 0;
+    // card picked by the player, lit until the given time
+    let mut picked: Option<(usize, f64)> = None;
+    let mut best = 0;
+
+    loop {
+        if is_quit_requested() {
+            return Exit::Quit;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return Exit::Menu;
+        }
+
+        let navs = shared.poll(false);
+        let now = get_time();
+        let score = sequence.len() - 1;
+
+        let mut shown = None;
+        match phase {
+            Phase::Show(start) if now >= start => {
+                let step = ((now - start) / (LIT_FOR + DARK_FOR)) as usize;
+                let into_step = (now - start) % (LIT_FOR + DARK_FOR);
+                if step >= sequence.len() {
+                    phase = Phase::Repeat(0);
+                } else if into_step < LIT_FOR {
+                    shown = Some(sequence[step]);
+                }
+            }
+            Phase::Repeat(done) => {
+                if let Some(i) = pick_card(&cards, &mut focus, navs) {
+                    picked = Some((i, now + LIT_FOR / 2.0));
+                    shared.audio.play(Sfx::Flip);
+
+                    if sequence[done] != i {
+                        shared.audio.play(Sfx::Mismatch);
+                        best = best.max(score);
+                        phase = Phase::Over;
+                    } else if done + 1 < sequence.len() {
+                        phase = Phase::Repeat(done + 1);
+                    } else {
+                        shared.audio.play(Sfx::Match);
+                        sequence.push(rand::gen_range(0, CARDS));
+                        phase = Phase::Show(now + PAUSE_BEFORE_SHOW);
+                    }
+                }
+            }
+            Phase::Over if navs.contains(&Nav::Flip) => {
+                cards = deal_cards(shared).await;
+                sequence = vec![rand::gen_range(0, CARDS)];
+                phase = Phase::Show(now + PAUSE_BEFORE_SHOW);
+                picked = None;
+                focus = 0;
+            }
+            _ => {}
+        }
+
+        // draw
+        let lit = picked.filter(|(_, until)| now < *until).map(|(i, _)| i);
+        for (i, c) in cards.iter().enumerate() {
+            let is_open = shown == Some(i) || lit == Some(i);
+            c.draw(&shared.textures[&c.id], is_open, false);
+            if i == focus && matches!(phase, Phase::Repeat(_)) {
+                c.draw_focus();
+            }
+        }
+
+        let status = match phase {
+            Phase::Show(_) => format!("Watch...   score {}   best {}", score, best),
+            Phase::Repeat(_) => format!("Your turn!   score {}   best {}", score, best),
+            Phase::Over => format!(
+                "Oops! You repeated {} cards. Space to play again, Esc for menu",
+                score
+            ),
+        };
+        draw_status(&status);
+        shared.draw_overlays();
+
+        next_frame().await;
+    }
+}
+
+async fn deal_cards(shared: &mut Shared) -> Vec<Card> {
+    let ids: Vec<String> = shared.images.choose_multiple(CARDS).cloned().collect();
+    let cards = layout_cards(ids);
+    load_textures(&mut shared.textures, &cards).await.unwrap();
+    cards
+}
@@ -0,0 +1,143 @@
+use crate::audio::Sfx;
+use crate::input::Nav;
+use crate::{draw_status, layout_cards, load_textures, pick_card, Card, Exit, Shared};
+use macroquad::prelude::*;
+use macroquad::rand::ChooseRandom;
+
+const START_GRID: usize = 4;
+const MAX_GRID: usize = 12;
+const LIVES: u32 = 3;
+const STUDY_FOR: f64 = 2.0;
+const STUDY_PER_CARD: f64 = 0.5;
+const HIDDEN_FOR: f64 = 1.0;
+const REVEAL_FOR: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    /// cards face up to remember, until the given time
+    Study(f64),
+    /// cards face down while one of them changes, until the given time
+    Hidden(f64),
+    /// cards face up again, find the changed one
+    Spot,
+    /// the changed card is shown, until the given time
+    Reveal(f64),
+    Over,
+}
+
+struct Round {
+    cards: Vec<Card>,
+    changed: usize,
+    newcomer: String,
+}
+
+/// Remember a grid, then find the one card that changed while it was hidden.
+/// A right answer scores one point per card on the grid and grows it,
+/// a wrong one costs a life.
+pub async fn play(shared: &mut Shared) -> Exit {
+    let mut size = START_GRID;
+    let mut round = new_round(shared, size).await;
+    let mut phase = Phase::Study(get_time() + study_time(size));
+    let mut focus: usize = 0;
+    let mut score = 0;
+    let mut lives = LIVES;
+    let mut best = 0;
+
+    loop {
+        if is_quit_requested() {
+            return Exit::Quit;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return Exit::Menu;
+        }
+
+        let navs = shared.poll(false);
+        let now = get_time();
+
+        match phase {
+            Phase::Study(until) if now >= until => {
+                phase = Phase::Hidden(now + HIDDEN_FOR);
+            }
+            Phase::Hidden(until) if now >= until => {
+                round.cards[round.changed].id = round.newcomer.clone();
+                phase = Phase::Spot;
+            }
+            Phase::Spot => {
+                if let Some(i) = pick_card(&round.cards, &mut focus, navs) {
+                    if i == round.changed {
+                        shared.audio.play(Sfx::Match);
+                        score += size;
+                        size = (size + 1).min(MAX_GRID);
+                    } else {
+                        shared.audio.play(Sfx::Mismatch);
+                        lives -= 1;
+                    }
+                    phase = Phase::Reveal(now + REVEAL_FOR);
+                }
+            }
+            Phase::Reveal(until) if now >= until => {
+                if lives == 0 {
+                    best = best.max(score);
+                    phase = Phase::Over;
+                } else {
+                    round = new_round(shared, size).await;
+                    phase = Phase::Study(now + study_time(size));
+                    focus = 0;
+                }
+            }
+            Phase::Over if navs.contains(&Nav::Flip) => {
+                size = START_GRID;
+                score = 0;
+                lives = LIVES;
+                round = new_round(shared, size).await;
+                phase = Phase::Study(now + study_time(size));
+                focus = 0;
+            }
+            _ => {}
+        }
+
+        // draw
+        for (i, c) in round.cards.iter().enumerate() {
+            let is_open = !matches!(phase, Phase::Hidden(_));
+            let is_revealed = i == round.changed && matches!(phase, Phase::Reveal(_) | Phase::Over);
+            c.draw(&shared.textures[&c.id], is_open, is_revealed);
+            if i == focus && matches!(phase, Phase::Spot) {
+                c.draw_focus();
+            }
+        }
+
+        let status = match phase {
+            Phase::Study(_) => format!("Remember the cards!   score {}   lives {}", score, lives),
+            Phase::Hidden(_) => "...".to_string(),
+            Phase::Spot | Phase::Reveal(_) => {
+                format!("Which card is new?   score {}   lives {}", score, lives)
+            }
+            Phase::Over => format!(
+                "Game over! Score {}, best {}. Space to play again, Esc for menu",
+                score, best
+            ),
+        };
+        draw_status(&status);
+        shared.draw_overlays();
+
+        next_frame().await;
+    }
+}
+
+fn study_time(size: usize) -> f64 {
+    STUDY_FOR + STUDY_PER_CARD * size as f64
+}
+
+async fn new_round(shared: &mut Shared, size: usize) -> Round {
+    let ids: Vec<String> = shared.images.choose_multiple(size + 1).cloned().collect();
+    let mut cards = layout_cards(ids);
+    // the newcomer is loaded with the rest, so the swap does not stall
+    load_textures(&mut shared.textures, &cards).await.unwrap();
+    let newcomer = cards.pop().unwrap().id;
+
+    Round {
+        changed: rand::gen_range(0, cards.len()),
+        cards,
+        newcomer,
+    }
+}
@@ -0,0 +1,70 @@
+use macroquad::prelude::*;
+
+/// Whether `moving` touches `target` anywhere on its way `delta` further,
+/// so a drop fast enough to skip past the bucket between two frames is
+/// still caught. `delta` is relative to `target`, which stays where it is.
+pub fn swept(moving: Rect, delta: Vec2, target: Rect) -> bool {
+    // grow the target by the moving rect, then follow its corner as a point
+    let grown = Rect::new(target.x - moving.w, target.y - moving.h, target.w + moving.w, target.h + moving.h);
+    let (mut enter, mut leave) = (0.0_f32, 1.0_f32);
+    for (start, d, min, max) in [(moving.x, delta.x, grown.x, grown.right()), (moving.y, delta.y, grown.y, grown.bottom())] {
+        if d == 0.0 {
+            if start < min || start > max {
+                return false;
+            }
+            continue;
+        }
+        let (a, b) = ((min - start) / d, (max - start) / d);
+        enter = enter.max(a.min(b));
+        leave = leave.min(a.max(b));
+        if enter > leave {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUCKET: Rect = Rect { x: 100.0, y: 500.0, w: 80.0, h: 20.0 };
+
+    #[test]
+    fn fast_drop_over_the_bucket() {
+        let number = Rect::new(130.0, 400.0, 20.0, 30.0);
+        let fall = vec2(0.0, 200.0);
+        // neither frame touches the bucket on its own
+        assert!(!number.overlaps(&BUCKET));
+        assert!(!number.offset(fall).overlaps(&BUCKET));
+        assert!(swept(number, fall, BUCKET));
+    }
+
+    #[test]
+    fn miss_beside_the_bucket() {
+        assert!(!swept(Rect::new(300.0, 400.0, 20.0, 30.0), vec2(0.0, 200.0), BUCKET));
+        assert!(!swept(Rect::new(50.0, 400.0, 20.0, 30.0), vec2(0.0, 200.0), BUCKET));
+        // passing by sideways above it
+        assert!(!swept(Rect::new(0.0, 300.0, 20.0, 30.0), vec2(300.0, 50.0), BUCKET));
+    }
+
+    #[test]
+    fn no_sideways_move() {
+        assert!(swept(Rect::new(170.0, 480.0, 20.0, 30.0), vec2(0.0, 5.0), BUCKET));
+        assert!(swept(Rect::new(130.0, 495.0, 20.0, 30.0), Vec2::ZERO, BUCKET));
+        assert!(!swept(Rect::new(130.0, 400.0, 20.0, 30.0), Vec2::ZERO, BUCKET));
+    }
+
+    #[test]
+    fn bucket_moving_towards_the_number() {
+        // the number falls straight down at x 10 while the bucket rushes
+        // right from x -50 to 100, they meet halfway
+        let number = Rect::new(10.0, 400.0, 20.0, 30.0);
+        let bucket_moved = 150.0;
+        let fall = vec2(0.0, 200.0);
+        assert!(!swept(number, fall, BUCKET));
+        assert!(swept(number.offset(vec2(bucket_moved, 0.0)), vec2(-bucket_moved, fall.y), BUCKET));
+        // moving away it never reaches the number
+        assert!(!swept(number.offset(vec2(-bucket_moved, 0.0)), vec2(bucket_moved, fall.y), BUCKET));
+    }
+}
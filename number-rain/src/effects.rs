@@ -0,0 +1,203 @@
+use macroquad::prelude::*;
+use std::f32::consts::PI;
+use std::io::{Cursor, Write};
+
+/// Particles alive at once, the oldest makes room when all are in use.
+const PARTICLES: usize = 256;
+const POPUPS: usize = 8;
+
+const GRAVITY: f32 = 900.0;
+const SPLASH_COUNT: usize = 14;
+const SPLASH_SPEED: f32 = 260.0;
+const CONFETTI_COUNT: usize = 160;
+const POPUP_RISE: f32 = 80.0;
+/// Popups start this far above the catch, clear of what is written over the bucket.
+const POPUP_ABOVE: f32 = 60.0;
+const POPUP_SECONDS: f32 = 0.8;
+const SQUASH_SECONDS: f32 = 0.35;
+/// How much wider the bucket gets when a number lands in it.
+const SQUASH_AMOUNT: f32 = 0.25;
+const SHAKE_SECONDS: f32 = 0.4;
+const SHAKE_PIXELS: f32 = 12.0;
+
+const CONFETTI_COLORS: [Color; 6] = [RED, ORANGE, YELLOW, GREEN, SKYBLUE, VIOLET];
+
+/// Counts from 0 to 1 over `duration` seconds, then stays at 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tween {
+    time: f32,
+    duration: f32,
+}
+
+impl Tween {
+    pub fn start(&mut self, duration: f32) {
+        self.time = 0.0;
+        self.duration = duration;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time = (self.time + dt).min(self.duration);
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 { 1.0 } else { self.time / self.duration }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.progress() < 1.0
+    }
+
+    /// Fast at the start and gently to the end.
+    pub fn ease_out(&self) -> f32 {
+        1.0 - (1.0 - self.progress()).powi(3)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    color: Color,
+    size: f32,
+    life: Tween,
+}
+
+/// A number floating up from where it landed, `-7` or `+3` for a refund.
+#[derive(Debug, Clone, Copy, Default)]
+struct Popup {
+    position: Vec2,
+    /// written once, so drawing does not format every frame
+    label: [u8; 12],
+    len: usize,
+    color: Color,
+    life: Tween,
+}
+
+impl Popup {
+    fn label(&self) -> &str {
+        std::str::from_utf8(&self.label[..self.len]).unwrap_or("")
+    }
+}
+
+/// Everything that makes a catch feel like one: splashes, popups, a
+/// squashed bucket, a shaking screen and confetti. Particles and popups
+/// live in fixed pools, so a busy screen costs no allocations.
+pub struct Effects {
+    particles: [Particle; PARTICLES],
+    next_particle: usize,
+    popups: [Popup; POPUPS],
+    next_popup: usize,
+    squash: Tween,
+    shake: Tween,
+    /// picked once a frame, so everything shakes the same way
+    shake_offset: Vec2,
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        Effects {
+            particles: [Particle::default(); PARTICLES],
+            next_particle: 0,
+            popups: [Popup::default(); POPUPS],
+            next_popup: 0,
+            squash: Tween::default(),
+            shake: Tween::default(),
+            shake_offset: Vec2::ZERO,
+        }
+    }
+
+    /// Drops whatever is still going on, for a new game.
+    pub fn clear(&mut self) {
+        *self = Effects::new();
+    }
+
+    /// A splash and the value taken from the bucket, at `at`.
+    pub fn catch(&mut self, at: Vec2, value: i32, color: Color) {
+        for _ in 0..SPLASH_COUNT {
+            // upwards, spread over half a circle
+            let angle = rand::gen_range(PI * 1.1, PI * 1.9);
+            let velocity = Vec2::from_angle(angle) * rand::gen_range(0.4, 1.0) * SPLASH_SPEED;
+            self.emit(at, velocity, color, rand::gen_range(3.0, 6.0), rand::gen_range(0.3, 0.6));
+        }
+
+        let popup = &mut self.popups[self.next_popup];
+        *popup = Popup { position: at - vec2(0.0, POPUP_ABOVE), color, ..Popup::default() };
+        let mut cursor = Cursor::new(&mut popup.label[..]);
+        popup.len = write!(cursor, "{:+}", -value).map_or(0, |_| cursor.position() as usize);
+        popup.life.start(POPUP_SECONDS);
+        self.next_popup = (self.next_popup + 1) % POPUPS;
+
+        self.squash.start(SQUASH_SECONDS);
+    }
+
+    /// Too much in the bucket.
+    pub fn overshoot(&mut self) {
+        self.shake.start(SHAKE_SECONDS);
+    }
+
+    /// Confetti over the whole `area`, falling from its top.
+    pub fn win(&mut self, area: Rect) {
+        for i in 0..CONFETTI_COUNT {
+            let at = vec2(rand::gen_range(area.x, area.right()), area.y + rand::gen_range(0.0, area.h / 4.0));
+            let velocity = vec2(rand::gen_range(-120.0, 120.0), rand::gen_range(-400.0, -100.0));
+            let color = CONFETTI_COLORS[i % CONFETTI_COLORS.len()];
+            self.emit(at, velocity, color, rand::gen_range(4.0, 8.0), rand::gen_range(1.5, 3.0));
+        }
+    }
+
+    fn emit(&mut self, position: Vec2, velocity: Vec2, color: Color, size: f32, seconds: f32) {
+        let particle = &mut self.particles[self.next_particle];
+        *particle = Particle { position, velocity, color, size, life: Tween::default() };
+        particle.life.start(seconds);
+        self.next_particle = (self.next_particle + 1) % PARTICLES;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for p in self.particles.iter_mut().filter(|p| p.life.is_running()) {
+            p.velocity.y += GRAVITY * dt;
+            p.position += p.velocity * dt;
+            p.life.update(dt);
+        }
+        for p in self.popups.iter_mut().filter(|p| p.life.is_running()) {
+            p.life.update(dt);
+        }
+        self.squash.update(dt);
+        self.shake.update(dt);
+
+        self.shake_offset = if self.shake.is_running() {
+            let strength = (1.0 - self.shake.ease_out()) * SHAKE_PIXELS;
+            vec2(rand::gen_range(-1.0, 1.0), rand::gen_range(-1.0, 1.0)) * strength
+        } else {
+            Vec2::ZERO
+        };
+    }
+
+    /// Width and height scale of the bucket, wide and flat right after a
+    /// catch and wobbling back to 1.
+    pub fn bucket_scale(&self) -> Vec2 {
+        if !self.squash.is_running() {
+            return Vec2::ONE;
+        }
+        let t = self.squash.progress();
+        let wobble = (t * PI * 3.0).cos() * (1.0 - t) * SQUASH_AMOUNT;
+        vec2(1.0 + wobble, 1.0 - wobble)
+    }
+
+    /// How far to move everything this frame while the screen shakes.
+    pub fn shake(&self) -> Vec2 {
+        self.shake_offset
+    }
+
+    pub fn draw(&self, font_size: f32) {
+        let shake = self.shake();
+        for p in self.particles.iter().filter(|p| p.life.is_running()) {
+            let color = Color { a: 1.0 - p.life.progress(), ..p.color };
+            draw_rectangle(p.position.x + shake.x, p.position.y + shake.y, p.size, p.size, color);
+        }
+        for p in self.popups.iter().filter(|p| p.life.is_running()) {
+            let color = Color { a: 1.0 - p.life.progress(), ..p.color };
+            let y = p.position.y - p.life.ease_out() * POPUP_RISE;
+            draw_text(p.label(), p.position.x + shake.x, y + shake.y, font_size, color);
+        }
+    }
+}
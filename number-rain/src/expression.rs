@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+
+/// Largest term added or taken away to build an equation.
+const MAX_TERM: i32 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Plus,
+    Minus,
+    Times,
+}
+
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Times => "×",
+        }
+    }
+}
+
+/// What falls: a plain number, or a small equation to work out first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expression {
+    Literal(i32),
+    Binary(i32, Operator, i32),
+}
+
+impl Expression {
+    pub fn value(&self) -> i32 {
+        match *self {
+            Expression::Literal(n) => n,
+            Expression::Binary(a, Operator::Plus, b) => a + b,
+            Expression::Binary(a, Operator::Minus, b) => a - b,
+            Expression::Binary(a, Operator::Times, b) => a * b,
+        }
+    }
+
+    /// `7`, `3+4` or `2×5`, as drawn on screen.
+    pub fn label(&self) -> String {
+        match self {
+            Expression::Literal(n) => n.to_string(),
+            Expression::Binary(a, operator, b) => format!("{}{}{}", a, operator.symbol(), b),
+        }
+    }
+
+    /// A random equation that works out to `value`. A product only when
+    /// `value` has a factor other than one and itself.
+    pub fn equation(value: i32) -> Self {
+        let mut forms = vec![];
+        if value >= 2 {
+            let a = rand::gen_range(1, value);
+            forms.push(Expression::Binary(a, Operator::Plus, value - a));
+        }
+        // refunds come out as a difference below zero, 2-5
+        let b = rand::gen_range(1, MAX_TERM + 1);
+        let b = if value < 0 { b - value } else { b };
+        forms.push(Expression::Binary(value + b, Operator::Minus, b));
+        let factors: Vec<i32> = (2..=value / 2).filter(|d| value % d == 0 && *d <= value / d).collect();
+        if !factors.is_empty() {
+            let d = factors[rand::gen_range(0, factors.len())];
+            let (a, b) = if rand::gen_range(0, 2) == 0 { (d, value / d) } else { (value / d, d) };
+            forms.push(Expression::Binary(a, Operator::Times, b));
+        }
+        forms[rand::gen_range(0, forms.len())]
+    }
+}
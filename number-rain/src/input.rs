@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+
+/// Stick values closer to the middle than this are ignored, sticks rarely rest at 0.
+const DEAD_ZONE: f32 = 0.2;
+/// Share of full speed the stick gains per second while held.
+const STICK_ACCELERATION: f32 = 3.0;
+/// Share of the way to the pointer the bucket covers per second, roughly.
+const FOLLOW_SMOOTHING: f32 = 12.0;
+
+/// How the bucket is steered this frame, whatever it is steered with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Steer {
+    Still,
+    /// a share of the bucket speed, -1.0 is left at full speed
+    Speed(f32),
+    /// the x the middle of the bucket should move to
+    Towards(f32),
+}
+
+/// Keys, a gamepad stick, the mouse and touches, read into one [`Steer`].
+/// The keys and the stick win over the pointer, which only takes over
+/// again once it moves, so a resting mouse does not pull the bucket back.
+pub struct Input {
+    last_mouse: Vec2,
+    pointer: bool,
+    /// builds up while the stick is held, so small moves stay precise
+    stick_speed: f32,
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<gilrs::Gilrs>,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Input {
+            last_mouse: Vec2::from(mouse_position()),
+            pointer: false,
+            stick_speed: 0.0,
+            #[cfg(feature = "gamepad")]
+            gamepads: gilrs::Gilrs::new().map_err(|e| eprintln!("playing without gamepads, {}", e)).ok(),
+        }
+    }
+
+    pub fn steer(&mut self, dt: f32) -> Steer {
+        let keys = match (is_key_down(KeyCode::Left), is_key_down(KeyCode::Right)) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+        if keys != 0.0 {
+            self.pointer = false;
+            return Steer::Speed(keys);
+        }
+
+        let stick = self.stick();
+        if stick.abs() > DEAD_ZONE {
+            self.pointer = false;
+            self.stick_speed = (self.stick_speed + STICK_ACCELERATION * dt).min(1.0);
+            // what is left past the dead zone, scaled back to 0..1
+            let push = (stick.abs() - DEAD_ZONE) / (1.0 - DEAD_ZONE);
+            return Steer::Speed(stick.signum() * push * self.stick_speed);
+        }
+        self.stick_speed = 0.0;
+
+        if let Some(touch) = touches().first() {
+            self.pointer = true;
+            return Steer::Towards(touch.position.x);
+        }
+        let mouse = Vec2::from(mouse_position());
+        if mouse != self.last_mouse || is_mouse_button_down(MouseButton::Left) {
+            self.pointer = true;
+        }
+        self.last_mouse = mouse;
+        if self.pointer {
+            Steer::Towards(mouse.x)
+        } else {
+            Steer::Still
+        }
+    }
+
+    /// Left stick of the first gamepad that is pushed, 0.0 without any.
+    #[cfg(feature = "gamepad")]
+    fn stick(&mut self) -> f32 {
+        let Some(gamepads) = self.gamepads.as_mut() else {
+            return 0.0;
+        };
+        // the state only updates as the events are read
+        while gamepads.next_event().is_some() {}
+        gamepads.gamepads()
+            .map(|(_, gamepad)| gamepad.value(gilrs::Axis::LeftStickX))
+            .find(|x| x.abs() > DEAD_ZONE)
+            .unwrap_or(0.0)
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn stick(&mut self) -> f32 {
+        0.0
+    }
+}
+
+/// Where the bucket at `x`, `width` wide, ends up this frame. It never
+/// goes faster than `speed`, even following the pointer, and always stays
+/// between `left` and `right`.
+pub fn move_bucket(x: f32, width: f32, steer: Steer, speed: f32, dt: f32, left: f32, right: f32) -> f32 {
+    let max_step = speed * dt;
+    let x = match steer {
+        Steer::Still => x,
+        Steer::Speed(share) => x + share.clamp(-1.0, 1.0) * max_step,
+        Steer::Towards(middle) => {
+            let gap = middle - width / 2.0 - x;
+            let smoothed = gap * (1.0 - (-FOLLOW_SMOOTHING * dt).exp());
+            x + smoothed.clamp(-max_step, max_step)
+        }
+    };
+    clamp(x, left, right - width)
+}
@@ -1,26 +1,48 @@
+mod collision;
+mod effects;
+mod expression;
+mod input;
+mod round;
+mod rules;
+
+use effects::Effects;
+use expression::Expression;
+use input::Input;
 use macroquad::prelude::*;
-
-const BUCKET_SPEED: f32 = 700.0;
-const MAX_BUCKET_CAP: i32 = 30;
-
-const MIN_NUMBER_SPEED: f32 = 200.0;
-const MAX_NUMBER_SPEED: f32 = 400.0;
-const NUMBER_PERCENT: i32 = 95;
-
+use round::Round;
+use rules::{Difficulty, Presets, RULES_FILE};
 
 const SCREEN_OFFSET: f32 = 30.0;
 
+const DIFFICULTY_KEYS: [(KeyCode, Difficulty); 3] = [
+    (KeyCode::Key1, Difficulty::Easy),
+    (KeyCode::Key2, Difficulty::Normal),
+    (KeyCode::Key3, Difficulty::Hard),
+];
+
 
 #[macroquad::main("number-rain")]
 async fn main() {
     rand::srand(miniquad::date::now() as u64);
 
+    let presets = Presets::load(RULES_FILE);
+    let mut difficulty = Difficulty::Normal;
+    let mut rules = presets.rules(difficulty);
+    // equations to work out instead of plain numbers
+    let mut equations = false;
+
     let mut game_over = false;
 
     let mut bucket_x = screen_width() / 2.0;
-    let mut bucket_cap = rand::gen_range(1, MAX_BUCKET_CAP);
+    let mut round = Round::new(1, rules);
+    let mut lives = rules.lives;
+    let mut spawn_in = rules.spawn_interval();
 
     let mut numbers = vec![];
+    // the last equation caught, worked out, and whether it fit
+    let mut last_catch: Option<(Expression, bool)> = None;
+    let mut effects = Effects::new();
+    let mut input = Input::new();
 
     loop {
         clear_background(LIGHTGRAY);
@@ -39,82 +61,143 @@ async fn main() {
         let bucket_fs = bucket_w.min(bucket_h);
 
         let delta_time = get_frame_time();
-        let bucket_mov = BUCKET_SPEED * delta_time;
 
-        if game_over && is_key_pressed(KeyCode::Space) {
+        // picking a difficulty starts over with its rules
+        let picked = DIFFICULTY_KEYS.iter().find(|(key, _)| is_key_pressed(*key)).map(|(_, d)| *d);
+        if let Some(d) = picked {
+            difficulty = d;
+            rules = presets.rules(difficulty);
+        }
+
+        let switched = is_key_pressed(KeyCode::Tab);
+        if switched {
+            equations = !equations;
+        }
+
+        if picked.is_some() || switched || game_over && is_key_pressed(KeyCode::Space) {
             numbers.clear();
             bucket_x = main_w / 2.0;
-            bucket_cap = rand::gen_range(1, MAX_BUCKET_CAP);
+            round = Round::new(1, rules);
+            lives = rules.lives;
+            last_catch = None;
+            effects.clear();
+            spawn_in = rules.spawn_interval();
             game_over = false;
         }
 
         if !game_over {
-            // numbers
-            if rand::gen_range(0, 100) >= NUMBER_PERCENT {
-                numbers.push(Number {
-                    value: rand::gen_range(1, MAX_BUCKET_CAP + 10),
-                    speed: rand::gen_range(MIN_NUMBER_SPEED, MAX_NUMBER_SPEED),
-                    x: rand::gen_range(SCREEN_OFFSET, screen_x),
-                    y: SCREEN_OFFSET * 2.0,
-                });
+            // numbers, by the clock so a faster screen does not rain harder
+            spawn_in -= delta_time;
+            while spawn_in <= 0.0 {
+                let falling: Vec<i32> = numbers.iter().map(|n: &Number| n.value).collect();
+                let value = round.next_value(&falling, rules);
+                let expression = if equations { Expression::equation(value) } else { Expression::Literal(value) };
+                let speed = rand::gen_range(rules.min_number_speed, rules.max_number_speed);
+                let mut number = Number::new(expression, bucket_fs, speed, SCREEN_OFFSET, SCREEN_OFFSET * 2.0);
+                // all of it on screen, equations are wider than numbers
+                number.x = rand::gen_range(SCREEN_OFFSET, (screen_x - number.text.width).max(SCREEN_OFFSET));
+                numbers.push(number);
+                spawn_in += rules.spawn_interval();
             }
 
-            if is_key_down(KeyCode::Left) {
-                bucket_x -= bucket_mov;
-            }
-            if is_key_down(KeyCode::Right) {
-                bucket_x += bucket_mov;
-            }
-            bucket_x = clamp(bucket_x, SCREEN_OFFSET, screen_x - bucket_w);
+            let bucket_before = bucket_x;
+            let steer = input.steer(delta_time);
+            bucket_x = input::move_bucket(bucket_x, bucket_w, steer, rules.bucket_speed, delta_time, SCREEN_OFFSET, screen_x);
 
-            for n in &mut numbers {
+            let bucket = Rect::new(bucket_x, bucket_y, bucket_w, bucket_h);
+            let bucket_moved = bucket_x - bucket_before;
+
+            numbers.retain_mut(|n| {
+                let before = n.bounds();
                 n.y += n.speed * delta_time;
-            }
 
-            numbers.retain(|n| n.y <= screen_y);
-            numbers.retain(|n| {
-                let collapsed = n.y >= bucket_y && n.y <= bucket_y + bucket_h &&
-                    n.x >= bucket_x && n.x <= bucket_x + bucket_w;
+                // seen from the bucket, which may have moved too, the number
+                // came from further along and went the other way sideways
+                let from = before.offset(vec2(bucket_moved, 0.0));
+                let collapsed = n.bounds().overlaps(&bucket) ||
+                    collision::swept(from, vec2(-bucket_moved, n.speed * delta_time), bucket);
 
+                // too much bounces off and costs a life
                 if collapsed {
-                    bucket_cap -= n.value;
+                    let fits = round.catch(n.value);
+                    if !fits {
+                        lives = lives.saturating_sub(1);
+                        effects.overshoot();
+                    }
+                    let color = if !fits { RED } else if n.value < 0 { DARKGREEN } else { GOLD };
+                    effects.catch(vec2(n.x, bucket_y), n.value, color);
+                    last_catch = Some((n.expression, fits));
                 }
-                !collapsed
+                !collapsed && n.y <= screen_y
             });
 
-            if bucket_cap <= 0 {
+            if lives == 0 {
                 game_over = true;
+            } else if round.is_cleared() {
+                if round.number >= rules.rounds {
+                    game_over = true;
+                    effects.win(Rect::new(SCREEN_OFFSET, SCREEN_OFFSET, main_w, main_h));
+                } else {
+                    round = Round::new(round.number + 1, rules);
+                    numbers.clear();
+                }
             }
         }
 
-        // draw
-        draw_rectangle(SCREEN_OFFSET, SCREEN_OFFSET, main_w, main_h, PINK);
+        effects.update(delta_time);
+
+        // draw, the play area shakes and the bucket squashes
+        let shake = effects.shake();
+        draw_rectangle(SCREEN_OFFSET + shake.x, SCREEN_OFFSET + shake.y, main_w, main_h, PINK);
 
         for n in &numbers {
-            draw_text(n.value.to_string().as_str(), n.x, n.y, bucket_fs, BLACK);
+            // refunds stand out, they take some back out of the bucket
+            let color = if n.value < 0 { DARKGREEN } else { BLACK };
+            draw_text(n.label.as_str(), n.x + shake.x, n.y + shake.y, bucket_fs, color);
         }
 
+        // squashed towards its bottom, where it stands
+        let scale = effects.bucket_scale();
+        let (squashed_w, squashed_h) = (bucket_w * scale.x, bucket_h * scale.y);
+        let squashed_x = bucket_x + (bucket_w - squashed_w) / 2.0 + shake.x;
+        let squashed_y = bucket_y + bucket_h - squashed_h + shake.y;
         draw_rectangle(
-            bucket_x,
-            bucket_y,
-            bucket_w,
-            bucket_h,
+            squashed_x,
+            squashed_y,
+            squashed_w,
+            squashed_h,
             DARKBLUE,
         );
         draw_text(
-            bucket_cap.to_string().as_str(),
-            bucket_x + (bucket_fs / 3.0),
-            bucket_y + (bucket_fs / 2.0),
+            round.remaining.to_string().as_str(),
+            squashed_x + (bucket_fs / 3.0),
+            squashed_y + (bucket_fs / 2.0),
             bucket_fs / 1.3,
             WHITE,
         );
 
+        if let Some((expression @ Expression::Binary(..), fits)) = last_catch {
+            let worked_out = format!("{} = {}", expression.label(), expression.value());
+            draw_text(worked_out.as_str(), bucket_x + shake.x, bucket_y - SCREEN_OFFSET / 2.0 + shake.y, SCREEN_OFFSET * 1.5, if fits { DARKGREEN } else { RED });
+        }
+
+        effects.draw(bucket_fs / 1.5);
+
+        let mut label_x = SCREEN_OFFSET;
+        for (key, d) in DIFFICULTY_KEYS {
+            let label = format!("{:?} {}", key, d.value()).replace("Key", "");
+            let color = if d == difficulty { DARKBLUE } else { GRAY };
+            label_x += draw_text(label.as_str(), label_x, SCREEN_OFFSET * 0.8, SCREEN_OFFSET, color).width + SCREEN_OFFSET;
+        }
+        draw_text("Tab equations", label_x, SCREEN_OFFSET * 0.8, SCREEN_OFFSET, if equations { DARKBLUE } else { GRAY });
+        let status = format!("round {}/{}   target {}   lives {}", round.number, rules.rounds, round.target, lives);
+        let status_w = measure_text(status.as_str(), None, SCREEN_OFFSET as u16, 1.0).width;
+        draw_text(status.as_str(), screen_x - status_w, SCREEN_OFFSET * 0.8, SCREEN_OFFSET, DARKBLUE);
+
         if game_over {
-            if bucket_cap < 0 {
+            if lives == 0 {
                 draw_text("GAME OVER!!!", screen_x / 7.0, screen_y / 2.0, screen_x / 7.0, RED);
-            }
-
-            if bucket_cap == 0 {
+            } else {
                 draw_text("YOU WIN!!!", screen_x / 7.0, screen_y / 2.0, screen_x / 7.0, GREEN);
             }
         }
@@ -123,9 +206,34 @@ async fn main() {
     }
 }
 
+/// A falling number, worked out and measured once when it spawns.
 struct Number {
     speed: f32,
     x: f32,
     y: f32,
+    expression: Expression,
+    label: String,
     value: i32,
+    /// size of the label as drawn, `y` is its baseline
+    text: TextDimensions,
+}
+
+impl Number {
+    fn new(expression: Expression, font_size: f32, speed: f32, x: f32, y: f32) -> Self {
+        let label = expression.label();
+        Number {
+            speed,
+            x,
+            y,
+            expression,
+            text: measure_text(label.as_str(), None, font_size as u16, 1.0),
+            label,
+            value: expression.value(),
+        }
+    }
+
+    /// Where the label is on screen, the part that can hit the bucket.
+    fn bounds(&self) -> Rect {
+        Rect::new(self.x, self.y - self.text.offset_y, self.text.width, self.text.height)
+    }
 }
@@ -0,0 +1,116 @@
+use crate::rules::Rules;
+use macroquad::prelude::*;
+
+/// Largest number a refund drop takes back out of the bucket.
+const MAX_REFUND: i32 = 9;
+
+/// One target to fill exactly. Overshooting leaves the bucket as it was,
+/// the caller takes a life for it.
+pub struct Round {
+    pub number: u32,
+    pub target: i32,
+    pub remaining: i32,
+}
+
+impl Round {
+    /// Targets grow with every round, up to the largest the rules allow.
+    pub fn new(number: u32, rules: &Rules) -> Self {
+        let top = (rules.first_target * number as i32).clamp(1, rules.max_bucket_cap.max(1));
+        let target = rand::gen_range(top * 2 / 3, top + 1).max(1);
+        Round {
+            number,
+            target,
+            remaining: target,
+        }
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Takes a caught number, false when it would overshoot. A refund
+    /// never takes out more than the bucket holds.
+    pub fn catch(&mut self, value: i32) -> bool {
+        let after = self.remaining - value;
+        if after < 0 {
+            return false;
+        }
+        self.remaining = after.min(self.target);
+        true
+    }
+
+    /// Value of the next number to fall. When the numbers already falling
+    /// cannot add up to what is left, this is the missing part, so there
+    /// is always a way to hit the target exactly. Otherwise it is any
+    /// number, or now and then a refund once something has been caught.
+    pub fn next_value(&self, falling: &[i32], rules: &Rules) -> i32 {
+        let best = closest_sum(self.remaining, falling);
+        if best < self.remaining {
+            return self.remaining - best;
+        }
+        let caught = self.target - self.remaining;
+        if caught > 0 && rand::gen_range(0, 100) < rules.refund_percent {
+            return -rand::gen_range(1, caught.min(MAX_REFUND) + 1);
+        }
+        // a few too big to take, that is what refunds are for
+        rand::gen_range(1, self.remaining + self.remaining / 2 + 1)
+    }
+}
+
+/// Largest sum of some of the positive `values` that is not over `limit`.
+fn closest_sum(limit: i32, values: &[i32]) -> i32 {
+    let limit = limit.max(0) as usize;
+    let mut reachable = vec![false; limit + 1];
+    reachable[0] = true;
+    for v in values.iter().filter(|v| **v > 0).map(|v| *v as usize) {
+        for sum in (v..=limit).rev() {
+            reachable[sum] = reachable[sum] || reachable[sum - v];
+        }
+    }
+    reachable.iter().rposition(|r| *r).unwrap_or(0) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(target: i32, remaining: i32) -> Round {
+        Round { number: 1, target, remaining }
+    }
+
+    #[test]
+    fn missing_part_when_falling_cannot_reach() {
+        let rules = Rules::default();
+        assert_eq!(round(20, 12).next_value(&[], &rules), 12);
+        assert_eq!(round(20, 12).next_value(&[5, 4], &rules), 3);
+        // too big and refunds do not help getting there
+        assert_eq!(round(20, 12).next_value(&[13, -3], &rules), 12);
+        for seed in 0..50 {
+            rand::srand(seed);
+            assert_ne!(round(20, 12).next_value(&[7, 5], &rules), 0);
+        }
+    }
+
+    #[test]
+    fn refunds_never_exceed_caught() {
+        let rules = Rules { refund_percent: 100, ..Rules::default() };
+        for seed in 0..200 {
+            rand::srand(seed);
+            let value = round(20, 17).next_value(&[17], &rules);
+            assert!((-3..0).contains(&value), "{}", value);
+            rand::srand(seed);
+            assert!(round(20, 20).next_value(&[20], &rules) > 0);
+        }
+    }
+
+    #[test]
+    fn catch_caps_at_target() {
+        let mut round = round(10, 4);
+        assert!(!round.catch(5));
+        assert_eq!(round.remaining, 4);
+        assert!(round.catch(-9));
+        assert_eq!(round.remaining, 10);
+        assert!(round.catch(10));
+        assert!(round.is_cleared());
+    }
+}
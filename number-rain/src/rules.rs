@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Presets are read from here. A preset left out keeps its built-in rules,
+/// a field left out takes the normal value.
+pub const RULES_FILE: &str = "rules.json";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn value(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+/// Everything that makes a round easier or harder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub bucket_speed: f32,
+    /// the targets grow round by round up to this one
+    pub max_bucket_cap: i32,
+    /// target of the first round, each round adds as much again
+    pub first_target: i32,
+    pub rounds: u32,
+    /// catches that overshoot the target before the game is over
+    pub lives: u32,
+    /// chance of a number taking some of the bucket back out
+    pub refund_percent: i32,
+    pub min_number_speed: f32,
+    pub max_number_speed: f32,
+    /// on average, whatever the frame rate
+    pub spawns_per_second: f32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            bucket_speed: 700.0,
+            max_bucket_cap: 30,
+            first_target: 10,
+            rounds: 5,
+            lives: 3,
+            refund_percent: 10,
+            min_number_speed: 200.0,
+            max_number_speed: 400.0,
+            spawns_per_second: 3.0,
+        }
+    }
+}
+
+impl Rules {
+    /// Seconds until the next number, a bit random so they do not fall in step.
+    pub fn spawn_interval(&self) -> f32 {
+        rand::gen_range(0.5, 1.5) / self.spawns_per_second.max(0.1)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Presets {
+    pub easy: Rules,
+    pub normal: Rules,
+    pub hard: Rules,
+}
+
+impl Default for Presets {
+    fn default() -> Self {
+        Presets {
+            easy: Rules {
+                bucket_speed: 800.0,
+                max_bucket_cap: 20,
+                first_target: 5,
+                rounds: 5,
+                lives: 5,
+                refund_percent: 15,
+                min_number_speed: 120.0,
+                max_number_speed: 250.0,
+                spawns_per_second: 1.5,
+            },
+            normal: Rules::default(),
+            hard: Rules {
+                bucket_speed: 650.0,
+                max_bucket_cap: 50,
+                first_target: 15,
+                rounds: 5,
+                lives: 2,
+                refund_percent: 8,
+                min_number_speed: 300.0,
+                max_number_speed: 550.0,
+                spawns_per_second: 4.5,
+            },
+        }
+    }
+}
+
+impl Presets {
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                eprintln!("using the built-in rules, {}: {}", path, e);
+                Presets::default()
+            })
+    }
+
+    pub fn rules(&self, difficulty: Difficulty) -> &Rules {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::Operation;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Success rate the engine steers towards.
+const TARGET: f32 = 0.8;
+/// How far the recent success rate may drift from `TARGET` before the range moves.
+const TOLERANCE: f32 = 0.08;
+/// Answers between two range changes, so one lucky streak does not count.
+const ADJUST_EVERY: u32 = 5;
+/// Weight of the newest answer in the running averages.
+const SMOOTHING: f32 = 0.2;
+/// Answers slower than this make a skill show up more often.
+const SLOW_SECONDS: f32 = 8.0;
+/// Problems drawn before picking the one that trains the weakest skill.
+const CANDIDATES: usize = 6;
+
+const MIN_LIMIT: i32 = 5;
+const MAX_LIMIT: i32 = 100;
+const LIMIT_STEP: i32 = 3;
+const MIN_TABLES: i32 = 2;
+const MAX_TABLES: i32 = 12;
+
+/// The kind of problem, tracked on its own because each one is a
+/// different thing to learn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Skill {
+    /// a carry happens when the ones add up to ten or more, i.e. crossing ten
+    Add { carry: bool },
+    Sub { borrow: bool },
+    Times(i32),
+    Divide(i32),
+    /// sums of fractions with the same denominator
+    Fractions,
+    /// sums of numbers with tenths
+    Decimals,
+    /// naming the digit at a place
+    PlaceValue,
+}
+
+impl Skill {
+    pub fn of(a: i32, operation: Operation, b: i32) -> Self {
+        match operation {
+            Operation::Plus => Skill::Add { carry: a % 10 + b % 10 >= 10 },
+            Operation::Sub => Skill::Sub { borrow: a % 10 < b % 10 },
+            Operation::Mul => Skill::Times(b),
+            Operation::Div => Skill::Divide(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillStats {
+    pub attempts: u32,
+    /// running average of right (1) and wrong (0) answers
+    pub accuracy: f32,
+    /// running average of the seconds taken to answer
+    pub seconds: f32,
+}
+
+impl SkillStats {
+    fn new() -> Self {
+        SkillStats {
+            attempts: 0,
+            accuracy: TARGET,
+            seconds: 0.0,
+        }
+    }
+
+    /// How much this skill needs practice, 1.0 for an average one.
+    fn weight(&self) -> f32 {
+        let slow = if self.seconds > SLOW_SECONDS { 0.5 } else { 0.0 };
+        0.5 + (1.0 - self.accuracy) * 2.5 + slow
+    }
+}
+
+/// Learns from every answer and keeps the problems hard enough that about
+/// `TARGET` of them are answered right.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Model {
+    /// largest first operand for sums and differences
+    pub limit: i32,
+    /// highest times table in use
+    pub tables: i32,
+    /// running success rate over all skills
+    pub recent: f32,
+    since_adjust: u32,
+    skills: Vec<(Skill, SkillStats)>,
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Model {
+            limit: crate::NUM_LIMIT,
+            tables: 5,
+            recent: TARGET,
+            since_adjust: 0,
+            skills: vec![],
+        }
+    }
+}
+
+impl Model {
+    /// Picks a problem from the enabled operations, favouring weak skills.
+    pub fn pick(&self, operations: &[Operation], rng: &mut impl Rng) -> (i32, Operation, i32) {
+        let candidates: Vec<(i32, Operation, i32)> = (0..CANDIDATES)
+            .map(|_| {
+                let operation = *operations.choose(rng).unwrap_or(&Operation::Plus);
+                let (a, b) = operation.operands(self.limit, self.tables, rng);
+                (a, operation, b)
+            })
+            .collect();
+
+        let weights: Vec<f32> = candidates
+            .iter()
+            .map(|(a, operation, b)| self.stats(Skill::of(*a, *operation, *b)).map_or(1.0, SkillStats::weight))
+            .collect();
+
+        let mut roll = rng.random_range(0.0..weights.iter().sum());
+        for (candidate, weight) in candidates.iter().zip(weights) {
+            if roll < weight {
+                return *candidate;
+            }
+            roll -= weight;
+        }
+        candidates[0]
+    }
+
+    pub fn record(&mut self, skill: Skill, correct: bool, seconds: f32) {
+        let success = if correct { 1.0 } else { 0.0 };
+
+        let stats = match self.skills.iter_mut().find(|(s, _)| *s == skill) {
+            Some((_, stats)) => stats,
+            None => {
+                self.skills.push((skill, SkillStats::new()));
+                &mut self.skills.last_mut().unwrap().1
+            }
+        };
+        stats.accuracy += (success - stats.accuracy) * SMOOTHING;
+        stats.seconds = if stats.attempts == 0 { seconds } else { stats.seconds + (seconds - stats.seconds) * SMOOTHING };
+        stats.attempts += 1;
+
+        self.recent += (success - self.recent) * SMOOTHING;
+        self.since_adjust += 1;
+        if self.since_adjust >= ADJUST_EVERY {
+            self.adjust();
+        }
+    }
+
+    pub fn stats(&self, skill: Skill) -> Option<&SkillStats> {
+        self.skills.iter().find(|(s, _)| *s == skill).map(|(_, stats)| stats)
+    }
+
+    fn adjust(&mut self) {
+        // counted again from here even when the range stays, so a streak
+        // after a calm spell still needs `ADJUST_EVERY` answers
+        self.since_adjust = 0;
+        if self.recent > TARGET + TOLERANCE {
+            self.limit = (self.limit + LIMIT_STEP).min(MAX_LIMIT);
+            self.tables = (self.tables + 1).min(MAX_TABLES);
+        } else if self.recent < TARGET - TOLERANCE {
+            self.limit = (self.limit - LIMIT_STEP).max(MIN_LIMIT);
+            self.tables = (self.tables - 1).max(MIN_TABLES);
+        } else {
+            return;
+        }
+        // start the new range from neutral ground
+        self.recent = TARGET;
+    }
+}
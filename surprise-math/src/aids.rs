@@ -0,0 +1,137 @@
+use macroquad::prelude::*;
+use std::fs;
+use surprise_math::{Kind, Operation, Problem};
+
+const ANIMALS_DIR: &str = "images/animals";
+/// Above this the objects get too small to count, a number line is drawn instead.
+const MAX_OBJECTS: i32 = 20;
+const MAX_OBJECT_SIZE: f32 = 48.0;
+const FRAME_CELL: f32 = 28.0;
+
+/// Pictures under the equation for children who still count on their
+/// fingers: both numbers as groups of animals, the ones taken away crossed
+/// out, and a ten-frame or a number line.
+pub struct Aids {
+    animals: Vec<Texture2D>,
+}
+
+impl Aids {
+    /// Loads the animal sprites, falling back to `cat` when there are none.
+    pub async fn load(cat: &Texture2D) -> Self {
+        let mut paths: Vec<String> = fs::read_dir(ANIMALS_DIR)
+            .map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.path().display().to_string()).filter(|p| p.ends_with(".png")).collect())
+            .unwrap_or_default();
+        paths.sort();
+
+        let mut animals = vec![];
+        for path in paths {
+            match load_texture(&path).await {
+                Ok(texture) => animals.push(texture),
+                Err(e) => eprintln!("skipping {}, {}", path, e),
+            }
+        }
+        if animals.is_empty() {
+            animals.push(cat.clone());
+        }
+        Aids { animals }
+    }
+
+    /// Draws the aids for sums and differences asked the plain way, the
+    /// other kinds would give the answer away. `top` is where the free
+    /// space under the problem starts.
+    pub fn draw(&self, problem: &Problem, top: f32) {
+        let (a, operation, b) = problem.fact;
+        if problem.kind != Kind::Basic || !matches!(operation, Operation::Plus | Operation::Sub) {
+            return;
+        }
+
+        let result = operation.apply(a, b);
+        if a.max(result) > MAX_OBJECTS {
+            draw_number_line(a, result, top + 40.0);
+            return;
+        }
+
+        // the same animal for the whole problem
+        let animal = &self.animals[(a * 31 + b) as usize % self.animals.len()];
+        let size = draw_objects(animal, a, operation, b, top);
+        // the frames only when they fit, tiles may have taken their room
+        let frames_top = top + size + 20.0;
+        if frames_top + 2.0 * FRAME_CELL < screen_height() - 40.0 {
+            draw_ten_frames(a, operation, b, frames_top);
+        }
+    }
+}
+
+/// Draws the groups and returns the size of one object.
+fn draw_objects(animal: &Texture2D, a: i32, operation: Operation, b: i32, top: f32) -> f32 {
+    // a sum shows two groups, a difference the first group with some crossed out
+    let count = if operation == Operation::Plus { a + b } else { a };
+    let gap = if operation == Operation::Plus { 1.0 } else { 0.0 };
+    let size = (screen_width() * 0.9 / (count as f32 + gap) / 1.1).min(MAX_OBJECT_SIZE);
+
+    for i in 0..count {
+        let group_gap = if operation == Operation::Plus && i >= a { size } else { 0.0 };
+        let x = 20.0 + i as f32 * size * 1.1 + group_gap;
+        let params = DrawTextureParams {
+            dest_size: Some(vec2(size, size)),
+            ..Default::default()
+        };
+        draw_texture_ex(animal, x, top, WHITE, params);
+        if operation == Operation::Sub && i >= a - b {
+            cross_out(x, top, size);
+        }
+    }
+    size
+}
+
+fn draw_number_line(from: i32, to: i32, y: f32) {
+    let max = from.max(to);
+    let left = 20.0;
+    let step = (screen_width() - 2.0 * left) / max as f32;
+    let x = |n: i32| left + n as f32 * step;
+
+    draw_line(left, y, x(max), y, 2.0, DARKGRAY);
+    for n in 0..=max {
+        let label = n % 10 == 0 || n == from || n == to;
+        let tick = if label { 10.0 } else { 5.0 };
+        draw_line(x(n), y - tick, x(n), y + tick, 2.0, DARKGRAY);
+        if label {
+            draw_text(n.to_string().as_str(), x(n) - 8.0, y + 30.0, 24.0, DARKGRAY);
+        }
+    }
+
+    // the hop from the first number to the result
+    let height = 30.0;
+    draw_line(x(from), y, x(from), y - height, 3.0, ORANGE);
+    draw_line(x(from), y - height, x(to), y - height, 3.0, ORANGE);
+    draw_line(x(to), y - height, x(to), y, 3.0, ORANGE);
+    draw_circle(x(from), y, 6.0, GOLD);
+}
+
+/// Two ten-frames, the first number in gold, the added part in orange and
+/// the part taken away crossed out.
+fn draw_ten_frames(a: i32, operation: Operation, b: i32, top: f32) {
+    for frame in 0..2 {
+        for cell in 0..10 {
+            let n = frame * 10 + cell;
+            let x = 20.0 + frame as f32 * (FRAME_CELL * 5.0 + 20.0) + (cell % 5) as f32 * FRAME_CELL;
+            let y = top + (cell / 5) as f32 * FRAME_CELL;
+            draw_rectangle_lines(x, y, FRAME_CELL, FRAME_CELL, 2.0, GRAY);
+
+            let center = (x + FRAME_CELL / 2.0, y + FRAME_CELL / 2.0);
+            if n < a {
+                draw_circle(center.0, center.1, FRAME_CELL / 3.0, GOLD);
+                if operation == Operation::Sub && n >= a - b {
+                    cross_out(x + 4.0, y + 4.0, FRAME_CELL - 8.0);
+                }
+            } else if operation == Operation::Plus && n < a + b {
+                draw_circle(center.0, center.1, FRAME_CELL / 3.0, ORANGE);
+            }
+        }
+    }
+}
+
+fn cross_out(x: f32, y: f32, size: f32) {
+    draw_line(x, y, x + size, y + size, 3.0, RED);
+    draw_line(x + size, y, x, y + size, 3.0, RED);
+}
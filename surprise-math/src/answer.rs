@@ -0,0 +1,192 @@
+use macroquad::prelude::*;
+use surprise_math::Rational;
+
+const MAX_LEN: usize = 7;
+const TILE_HEIGHT: f32 = 80.0;
+const MAX_TILE_WIDTH: f32 = 180.0;
+const TILE_GAP: f32 = 20.0;
+/// Where the tiles start, below the problem.
+const TILES_TOP: f32 = 0.64;
+
+/// `<` and `>` share their keys with `,` and `.`, so shift is not needed.
+const RELATION_KEYS: [(KeyCode, char); 4] = [
+    (KeyCode::Comma, '<'),
+    (KeyCode::Period, '>'),
+    (KeyCode::Equal, '='),
+    (KeyCode::KpEqual, '='),
+];
+
+/// Tiles are picked by their position, 1 for the leftmost.
+const TILE_KEYS: [(KeyCode, KeyCode); 4] = [
+    (KeyCode::Key1, KeyCode::Kp1),
+    (KeyCode::Key2, KeyCode::Kp2),
+    (KeyCode::Key3, KeyCode::Kp3),
+    (KeyCode::Key4, KeyCode::Kp4),
+];
+
+/// The answer being typed: digits from the main row or the numpad, an
+/// optional leading minus, one `/` or `.` for fractions and decimals,
+/// backspace to fix mistakes and Enter to submit. Typed characters are
+/// read in the order they came, however many arrive in one frame.
+/// Comparisons take a single `<`, `>` or `=` instead. For children who
+/// cannot type yet the answer is one of a few big tiles, picked with the
+/// mouse, a touch, the number keys or the arrows and Enter.
+pub struct AnswerInput {
+    text: String,
+    submitted: bool,
+    relation: bool,
+    tiles: Vec<String>,
+    focus: usize,
+}
+
+impl AnswerInput {
+    pub fn new() -> Self {
+        // whatever was typed before the problem showed up is not an answer
+        clear_input_queue();
+        AnswerInput {
+            text: "".to_string(),
+            submitted: false,
+            relation: false,
+            tiles: vec![],
+            focus: 0,
+        }
+    }
+
+    pub fn tiles(tiles: Vec<String>) -> Self {
+        AnswerInput {
+            tiles,
+            ..AnswerInput::new()
+        }
+    }
+
+    pub fn has_tiles(&self) -> bool {
+        !self.tiles.is_empty()
+    }
+
+    pub fn relation() -> Self {
+        AnswerInput {
+            relation: true,
+            ..AnswerInput::new()
+        }
+    }
+
+    /// Applies this frame's keys, true on the frame the answer is submitted.
+    pub fn update(&mut self) -> bool {
+        // chars come out last typed first, and stay queued until read
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+        if self.submitted {
+            return false;
+        }
+        if self.has_tiles() {
+            return self.update_tiles();
+        }
+
+        if self.relation {
+            for (key, symbol) in RELATION_KEYS {
+                if is_key_pressed(key) {
+                    self.text = symbol.to_string();
+                }
+            }
+        } else {
+            for c in typed {
+                // some layouts put a comma on the numpad
+                let c = if c == ',' { '.' } else { c };
+                let fits = self.text.len() < MAX_LEN;
+                let accepted = match c {
+                    '0'..='9' => fits,
+                    '-' => self.text.is_empty(),
+                    // a fraction bar or a decimal point, only one of them per answer
+                    '/' | '.' => fits && !self.text.contains(['/', '.']),
+                    _ => false,
+                };
+                if accepted {
+                    self.text.push(c);
+                }
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.text.pop();
+        }
+
+        let enter = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter);
+        self.submitted = enter && self.is_complete();
+        self.submitted
+    }
+
+    fn update_tiles(&mut self) -> bool {
+        let count = self.tiles.len();
+        let mut picked = TILE_KEYS.iter().take(count).position(|(key, kp)| is_key_pressed(*key) || is_key_pressed(*kp));
+
+        if is_key_pressed(KeyCode::Left) {
+            self.focus = (self.focus + count - 1) % count;
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.focus = (self.focus + 1) % count;
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            picked = Some(self.focus);
+        }
+
+        let touched = touches().into_iter().find(|t| t.phase == TouchPhase::Started).map(|t| t.position);
+        let clicked = is_mouse_button_pressed(MouseButton::Left).then(|| Vec2::from(mouse_position()));
+        if let Some(at) = touched.or(clicked) {
+            picked = picked.or((0..count).find(|i| tile_rect(*i, count).contains(at)));
+        }
+
+        if let Some(i) = picked {
+            self.text = self.tiles[i].clone();
+            self.submitted = true;
+        }
+        self.submitted
+    }
+
+    /// The tiles under the problem, until one is picked.
+    pub fn draw_tiles(&self) {
+        if self.submitted {
+            return;
+        }
+        let count = self.tiles.len();
+        for (i, tile) in self.tiles.iter().enumerate() {
+            let rect = tile_rect(i, count);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(255, 248, 220, 255));
+            let border = if i == self.focus { GOLD } else { LIGHTGRAY };
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 6.0, border);
+
+            let font_size = rect.h * 0.7;
+            let dimensions = measure_text(tile, None, font_size as u16, 1.0);
+            let x = rect.x + (rect.w - dimensions.width) / 2.0;
+            let y = rect.y + (rect.h + dimensions.offset_y) / 2.0;
+            draw_text(tile, x, y, font_size, BLACK);
+            draw_text((i + 1).to_string().as_str(), rect.x + 8.0, rect.y + 22.0, 22.0, GRAY);
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn is_complete(&self) -> bool {
+        if self.relation {
+            return !self.text.is_empty();
+        }
+        // "3/" is not an answer yet, and neither is "3/0"
+        Rational::parse(&self.text).is_some()
+    }
+}
+
+/// Tile `i` of `count`, side by side and centred.
+fn tile_rect(i: usize, count: usize) -> Rect {
+    let width = ((screen_width() - TILE_GAP) / count as f32 - TILE_GAP).min(MAX_TILE_WIDTH);
+    let left = (screen_width() - count as f32 * (width + TILE_GAP) + TILE_GAP) / 2.0;
+    Rect::new(left + i as f32 * (width + TILE_GAP), screen_height() * TILES_TOP, width, TILE_HEIGHT)
+}
+
+/// Bottom of the tiles, where anything drawn below them can start.
+pub fn tiles_bottom() -> f32 {
+    screen_height() * TILES_TOP + TILE_HEIGHT
+}
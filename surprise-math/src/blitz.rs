@@ -0,0 +1,131 @@
+use crate::history::History;
+use crate::profile::Profile;
+use crate::{draw_problem, next_problem, record_answer, Settings};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use surprise_math::{Generator, Operation};
+
+const DURATION: f64 = 60.0;
+const BASE_POINTS: u32 = 10;
+/// Right answers in a row needed to raise the multiplier by one.
+const COMBO_STEP: u32 = 3;
+const MAX_MULTIPLIER: u32 = 5;
+/// Answers faster than this earn a bonus, more the faster they are.
+const BONUS_SECONDS: f32 = 5.0;
+const BONUS_PER_SECOND: f32 = 2.0;
+const FLASH_FOR: f64 = 0.3;
+
+/// Best blitz score for one set of operations, so a `+` only record is
+/// not compared with one that has all four.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub operations: Vec<Operation>,
+    pub score: u32,
+}
+
+/// Running score of a blitz round.
+struct Score {
+    points: u32,
+    combo: u32,
+    answered: u32,
+    right: u32,
+}
+
+impl Score {
+    fn multiplier(&self) -> u32 {
+        (1 + self.combo / COMBO_STEP).min(MAX_MULTIPLIER)
+    }
+
+    /// Adds the points for one answer and returns them.
+    fn add(&mut self, correct: bool, seconds: f32) -> u32 {
+        self.answered += 1;
+        if !correct {
+            self.combo = 0;
+            return 0;
+        }
+        self.right += 1;
+        let bonus = ((BONUS_SECONDS - seconds).max(0.0) * BONUS_PER_SECOND).round() as u32;
+        let points = (BASE_POINTS + bonus) * self.multiplier();
+        self.combo += 1;
+        self.points += points;
+        points
+    }
+}
+
+/// As many problems as possible in a minute. Every problem shows up at
+/// once, right answers in a row raise a multiplier and fast ones earn a
+/// bonus. Escape leaves at any time, only finished rounds set records.
+pub async fn play(generator: &mut Generator, profile: &mut Profile, history: &mut History, settings: &Settings) {
+    let operations = settings.operation_set();
+    let started = get_time();
+    let mut score = Score {
+        points: 0,
+        combo: 0,
+        answered: 0,
+        right: 0,
+    };
+    let (mut problem, mut answer) = next_problem(generator, settings, profile);
+    problem.show_all();
+    let mut asked_at = started;
+    // the last answer, shown until the given time
+    let mut flash: Option<(bool, u32, f64)> = None;
+    let mut best: Option<(u32, bool)> = None;
+
+    loop {
+        clear_background(WHITE);
+        let now = get_time();
+        let left = DURATION - (now - started);
+
+        if is_key_pressed(KeyCode::Escape) {
+            next_frame().await;
+            return;
+        }
+
+        if left <= 0.0 {
+            let (record, is_new) = *best.get_or_insert_with(|| {
+                let is_new = profile.set_blitz_record(&operations, score.points);
+                profile.store();
+                (profile.blitz_record(&operations), is_new)
+            });
+            draw_text("Time's up!", 20.0, screen_height() / 3.0, 80.0, GOLD);
+            let summary = format!("{} points, {} of {} right", score.points, score.right, score.answered);
+            draw_text(summary.as_str(), 20.0, screen_height() / 3.0 + 70.0, 50.0, BLACK);
+            let record = if is_new { "A new record!".to_string() } else { format!("Record: {}", record) };
+            draw_text(record.as_str(), 20.0, screen_height() / 3.0 + 130.0, 50.0, DARKGREEN);
+            draw_text("Escape to go back", 20.0, screen_height() - 20.0, 30.0, GRAY);
+            next_frame().await;
+            continue;
+        }
+
+        if answer.update() {
+            let seconds = (now - asked_at) as f32;
+            let correct = record_answer(profile, history, &problem, answer.text(), seconds);
+            let points = score.add(correct, seconds);
+            flash = Some((correct, points, now + FLASH_FOR));
+            (problem, answer) = next_problem(generator, settings, profile);
+            problem.show_all();
+            asked_at = now;
+        }
+
+        if let Some((correct, points, until)) = flash.filter(|(_, _, until)| now < *until) {
+            let color = if correct { GREEN } else { RED };
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color { a: ((until - now) / FLASH_FOR) as f32 * 0.3, ..color });
+            if correct {
+                draw_text(format!("+{}", points).as_str(), screen_width() * 0.75, screen_height() / 4.0, 60.0, DARKGREEN);
+            }
+        }
+
+        draw_problem(&problem, &answer, false);
+        answer.draw_tiles();
+
+        // time left along the top
+        draw_rectangle(0.0, 0.0, screen_width(), 12.0, LIGHTGRAY);
+        draw_rectangle(0.0, 0.0, screen_width() * (left / DURATION) as f32, 12.0, if left < 10.0 { RED } else { GOLD });
+        let status = format!("{:.0}s   {} points   combo ×{}", left.ceil(), score.points, score.multiplier());
+        draw_text(status.as_str(), 20.0, 45.0, 36.0, DARKGRAY);
+        let record = format!("record {}", profile.blitz_record(&operations));
+        draw_text(record.as_str(), 20.0, 85.0, 30.0, GRAY);
+
+        next_frame().await;
+    }
+}
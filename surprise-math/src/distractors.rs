@@ -0,0 +1,99 @@
+use crate::problem::{Kind, Problem, Relation, Token};
+use crate::rational::Rational;
+use crate::Operation;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Wrong answers that look right: one off, the typical mistake for the
+/// kind of problem and the digits swapped, in that order of preference.
+/// Never the solution, never negative and never the same value twice.
+pub fn distractors(problem: &Problem, count: usize) -> Vec<Token> {
+    let Some(solution) = problem.solution.number() else {
+        // a comparison only has three answers anyway
+        return [Relation::Less, Relation::Equal, Relation::Greater]
+            .into_iter()
+            .map(Token::Relation)
+            .filter(|t| *t != problem.solution)
+            .take(count)
+            .collect();
+    };
+
+    // one off is a tenth off for decimals and one part off for fractions
+    let step = match problem.solution {
+        Token::Decimal(_) => Rational::new(1, 10),
+        Token::Fraction(_, den) => Rational::new(1, den as i64),
+        _ => None,
+    }
+    .unwrap_or(Rational::integer(1));
+    let near = |k: i64| solution.checked_add(step.checked_mul(Rational::integer(k))?).map(|n| problem.solution.with_number(n));
+
+    let mut candidates: Vec<Option<Token>> = vec![near(1)];
+    candidates.extend(mistakes(problem).into_iter().map(Some));
+    candidates.push(near(-1));
+    if solution.is_integer() {
+        candidates.push(Some(Token::Number(swap_digits(solution.num()))));
+    }
+    // enough fallbacks to fill any count
+    candidates.extend((2..).map(near).take(count));
+
+    let mut picked: Vec<Token> = vec![];
+    for token in candidates.into_iter().flatten() {
+        let Some(n) = token.number() else { continue };
+        if n >= Rational::integer(0) && n != solution && !picked.iter().any(|p| p.number() == Some(n)) {
+            picked.push(token);
+        }
+        if picked.len() == count {
+            break;
+        }
+    }
+    picked
+}
+
+/// The solution and `count - 1` distractors in random order.
+pub fn choices(problem: &Problem, count: usize, rng: &mut impl Rng) -> Vec<Token> {
+    let mut choices = distractors(problem, count.saturating_sub(1));
+    choices.push(problem.solution);
+    choices.shuffle(rng);
+    choices
+}
+
+/// What comes out of the mistake children make most with this kind of
+/// problem, if it has one.
+fn mistakes(problem: &Problem) -> Vec<Token> {
+    let (a, operation, b) = problem.fact;
+    match (problem.kind, problem.solution) {
+        (Kind::Basic, _) => wrong_operation(a, operation, b).map(Rational::from).map(Token::Number).into_iter().collect(),
+        // 1/5 + 2/5 answered as 3/10, the denominators added too
+        (Kind::Fractions, Token::Fraction(_, den)) => vec![Token::Fraction(a + b, den * 2)],
+        // 0.7 + 0.5 answered as 0.12, the tenths added without carrying
+        (Kind::Decimals, _) if a % 10 + b % 10 >= 10 => {
+            let whole = (a / 10 + b / 10) as i64 * 100;
+            Rational::new(whole + (a % 10 + b % 10) as i64, 100).map(Token::Decimal).into_iter().collect()
+        }
+        // the tens digit of 47 answered as 7 or as 40
+        (Kind::PlaceValue, _) => {
+            let digit = a / b % 10;
+            let mut mistakes: Vec<Token> = [1, 10, 100].into_iter().filter(|p| *p != b && *p <= a).map(|p| Token::Number((a / p % 10).into())).collect();
+            mistakes.push(Token::Number((digit * b).into()));
+            mistakes
+        }
+        _ => vec![],
+    }
+}
+
+/// What comes out when the child does the other operation, `7 + 3` read
+/// as `7 - 3`. Only for plain problems, where the mix-up is a common one.
+fn wrong_operation(a: i32, operation: Operation, b: i32) -> Option<i32> {
+    match operation {
+        Operation::Plus => a.checked_sub(b),
+        Operation::Sub => a.checked_add(b),
+        // 3 × 4 answered as 3 + 4, 12 ÷ 3 as 12 - 3
+        Operation::Mul => a.checked_add(b),
+        Operation::Div => a.checked_sub(b),
+    }
+}
+
+/// 12 becomes 21, a single digit or a palindrome stays as it is.
+fn swap_digits(n: i64) -> Rational {
+    Rational::integer(n.to_string().chars().rev().collect::<String>().parse().unwrap_or(n))
+}
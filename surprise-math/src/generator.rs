@@ -0,0 +1,39 @@
+use crate::adaptive::Model;
+use crate::distractors;
+use crate::problem::{Kind, Problem, Token};
+use crate::Operation;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+
+/// Owns the one random number generator of the game, so the same seed
+/// always gives the same problems, on screen or on a worksheet.
+pub struct Generator {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// A problem of one of `kinds`, built on a fact the model picks from
+    /// `operations` unless it is about fractions, decimals or places.
+    pub fn problem(&mut self, operations: &[Operation], kinds: &[Kind], model: &Model) -> Problem {
+        let kind = *kinds.choose(&mut self.rng).unwrap_or(&Kind::Basic);
+        if !kind.uses_fact() {
+            return Problem::with_numbers(kind, &mut self.rng);
+        }
+        let fact = model.pick(operations, &mut self.rng);
+        Problem::new(kind, fact, &mut self.rng)
+    }
+
+    /// Answers to pick from for the problem, see [`distractors::choices`].
+    pub fn choices(&mut self, problem: &Problem, count: usize) -> Vec<Token> {
+        distractors::choices(problem, count, &mut self.rng)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::profile::Profile;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use surprise_math::{Kind, Operation};
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+/// Days shown in the accuracy trend, today last.
+const TREND_DAYS: usize = 7;
+const MISSED_SHOWN: usize = 5;
+/// Kinds with a row of their own, they are not about one operation.
+const KIND_ROWS: [Kind; 3] = [Kind::Fractions, Kind::Decimals, Kind::PlaceValue];
+
+/// One answer as it is written to the history file. Lines written before
+/// the question was kept read as a basic problem with an empty question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// seconds since the unix epoch
+    pub time: f64,
+    /// as it was asked, `3 + 4 - 2 = ?`
+    #[serde(default)]
+    pub question: String,
+    #[serde(default)]
+    pub kind: Kind,
+    /// the fact the problem was built around
+    pub a: i32,
+    pub operation: Operation,
+    pub b: i32,
+    pub answer: String,
+    pub correct: bool,
+    pub seconds: f32,
+}
+
+impl Entry {
+    fn day(&self) -> i64 {
+        (self.time / SECONDS_PER_DAY).floor() as i64
+    }
+}
+
+/// A row of the accuracy trend.
+#[derive(Debug, Clone, Copy)]
+enum Row {
+    /// every problem built on a fact with this operation
+    Operation(Operation),
+    /// a kind that is not about an operation
+    Kind(Kind),
+}
+
+impl Row {
+    fn label(&self) -> String {
+        match self {
+            Row::Operation(operation) => operation.value(),
+            Row::Kind(kind) => kind.value().to_string(),
+        }
+    }
+
+    fn contains(&self, e: &Entry) -> bool {
+        match self {
+            Row::Operation(operation) => e.kind.uses_fact() && e.operation == *operation,
+            Row::Kind(kind) => e.kind == *kind,
+        }
+    }
+}
+
+/// Every answer a profile ever gave, kept in an append-only JSON-lines
+/// file so parents can see what needs practice.
+pub struct History {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Reads the profile's history, skipping lines that cannot be parsed.
+    pub fn load(profile: &Profile) -> Self {
+        let path = profile.history_path();
+        let entries = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| eprintln!("skipping a line of {}, {}", path.display(), e))
+                    .ok()
+            })
+            .collect();
+        History { path, entries }
+    }
+
+    pub fn append(&mut self, entry: Entry) {
+        let written = self.write(&entry);
+        if let Err(e) = written {
+            eprintln!("could not write to {}, {}", self.path.display(), e);
+        }
+        self.entries.push(entry);
+    }
+
+    fn write(&self, entry: &Entry) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())
+    }
+
+    /// Share of right answers per day for the last `TREND_DAYS` days, `None`
+    /// on days nothing in the row was practised.
+    fn daily_accuracy(&self, row: Row, today: i64) -> Vec<Option<f32>> {
+        (0..TREND_DAYS as i64)
+            .rev()
+            .map(|ago| {
+                let answers: Vec<&Entry> = self.entries.iter()
+                    .filter(|e| row.contains(e) && e.day() == today - ago)
+                    .collect();
+                if answers.is_empty() {
+                    return None;
+                }
+                let right = answers.iter().filter(|e| e.correct).count();
+                Some(right as f32 / answers.len() as f32)
+            })
+            .collect()
+    }
+
+    /// Facts answered wrong most often, as `("7 + 5", times missed)`.
+    /// Problems not built on a fact count by their question instead.
+    fn most_missed(&self) -> Vec<(String, usize)> {
+        let mut missed: Vec<(String, usize)> = vec![];
+        for e in self.entries.iter().filter(|e| !e.correct) {
+            let fact = if e.kind.uses_fact() { format!("{} {} {}", e.a, e.operation.value(), e.b) } else { e.question.clone() };
+            match missed.iter_mut().find(|(f, _)| *f == fact) {
+                Some((_, count)) => *count += 1,
+                None => missed.push((fact, 1)),
+            }
+        }
+        missed.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        missed.truncate(MISSED_SHOWN);
+        missed
+    }
+
+    pub fn draw(&self) {
+        draw_text("Statistics", 20.0, 60.0, 60.0, GOLD);
+        draw_text(format!("{} answers so far", self.entries.len()).as_str(), 20.0, 100.0, 30.0, GRAY);
+
+        // one row of daily bars per operation, then one per kind that was
+        // practised and is not about an operation, today on the right
+        let today = (miniquad::date::now() / SECONDS_PER_DAY).floor() as i64;
+        let mut rows: Vec<Row> = [Operation::Plus, Operation::Sub, Operation::Mul, Operation::Div].into_iter().map(Row::Operation).collect();
+        rows.extend(KIND_ROWS.into_iter().filter(|k| self.entries.iter().any(|e| e.kind == *k)).map(Row::Kind));
+        let bar_width = 40.0;
+        let bar_height = ((screen_height() - 220.0) / rows.len() as f32 - 20.0).clamp(20.0, 60.0);
+        let mut y = 140.0;
+        for row in rows {
+            // the kind labels are longer than an operation sign
+            let font_size = if matches!(row, Row::Kind(_)) { 20.0 } else { 50.0 };
+            draw_text(row.label().as_str(), 20.0, y + bar_height, font_size, BLACK);
+            for (i, accuracy) in self.daily_accuracy(row, today).iter().enumerate() {
+                let x = 80.0 + i as f32 * (bar_width + 10.0);
+                draw_rectangle_lines(x, y, bar_width, bar_height, 2.0, LIGHTGRAY);
+                if let Some(accuracy) = accuracy {
+                    let color = if *accuracy >= 0.8 { GREEN } else if *accuracy >= 0.5 { ORANGE } else { RED };
+                    let height = bar_height * accuracy.max(0.05);
+                    draw_rectangle(x, y + bar_height - height, bar_width, height, color);
+                }
+            }
+            y += bar_height + 20.0;
+        }
+        draw_text("last 7 days, today on the right", 80.0, y, 24.0, GRAY);
+
+        let x = 80.0 + TREND_DAYS as f32 * (bar_width + 10.0) + 60.0;
+        draw_text("Most missed", x, 170.0, 36.0, BLACK);
+        let missed = self.most_missed();
+        if missed.is_empty() {
+            draw_text("nothing yet", x, 210.0, 30.0, GRAY);
+        }
+        for (i, (fact, count)) in missed.iter().enumerate() {
+            let line = format!("{}   {}×", fact, count);
+            draw_text(line.as_str(), x, 210.0 + i as f32 * 40.0, 30.0, RED);
+        }
+
+        draw_text("Tab to go back", 20.0, screen_height() - 20.0, 30.0, GRAY);
+    }
+}
@@ -0,0 +1,84 @@
+//! Problem generation and checking for surprise-math, without anything
+//! to draw, so it can be tested and used for worksheets on its own.
+//!
+//! A [`Generator`] owns the random number generator and builds a
+//! [`Problem`] around a fact picked by the adaptive [`Model`]. Every
+//! problem is checked by filling the answer in and evaluating both sides,
+//! with exact [`Rational`] numbers so fractions and decimals work too.
+
+pub mod adaptive;
+pub mod distractors;
+pub mod generator;
+pub mod problem;
+pub mod rational;
+
+pub use adaptive::{Model, Skill};
+pub use generator::Generator;
+pub use problem::{Kind, Place, Problem, Relation, Token};
+pub use rational::Rational;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Largest first operand of sums and differences for a new profile.
+pub const NUM_LIMIT: i32 = 20;
+/// Largest factor and quotient taken from the times tables.
+pub const TABLES_LIMIT: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+    Plus,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operation {
+    pub fn value(&self) -> String {
+        match self {
+            Operation::Plus => "+".to_string(),
+            Operation::Sub => "-".to_string(),
+            Operation::Mul => "×".to_string(),
+            Operation::Div => "÷".to_string()
+        }
+    }
+
+    /// `a op b`, a division rounds towards zero and `b` must not be zero.
+    pub fn apply(&self, a: i32, b: i32) -> i32 {
+        match self {
+            Operation::Plus => a + b,
+            Operation::Sub => a - b,
+            Operation::Mul => a * b,
+            Operation::Div => a / b,
+        }
+    }
+
+    /// Operands that suit the operation: sums and differences stay within
+    /// `limit` and never go below zero, products and divisions use the
+    /// tables up to `tables` and divisions are exact.
+    pub fn operands(&self, limit: i32, tables: i32, rng: &mut impl Rng) -> (i32, i32) {
+        match self {
+            Operation::Plus | Operation::Sub => {
+                // at least 2, so there is room for b below it
+                let a = rng.random_range(2..=limit.max(2));
+                (a, rng.random_range(1..a))
+            }
+            Operation::Mul => (rng.random_range(1..=TABLES_LIMIT), rng.random_range(1..=tables.max(1))),
+            Operation::Div => {
+                let b = rng.random_range(1..=tables.max(1));
+                (b * rng.random_range(1..=TABLES_LIMIT), b)
+            }
+        }
+    }
+
+    /// `+`, `-`, `x` or `*`, `/` or `:`, and the symbols shown on screen.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '+' => Some(Operation::Plus),
+            '-' => Some(Operation::Sub),
+            'x' | '*' | '×' => Some(Operation::Mul),
+            '/' | ':' | '÷' => Some(Operation::Div),
+            _ => None,
+        }
+    }
+}
@@ -1,194 +1,439 @@
+mod aids;
+mod answer;
+mod blitz;
+mod history;
+mod mascot;
+mod narration;
+mod profile;
+mod session;
+mod worksheet;
+
+use aids::Aids;
+use answer::AnswerInput;
+use history::{Entry, History};
+use mascot::{Mascot, Mood};
+use narration::Narrator;
 use macroquad::prelude::next_frame;
 use macroquad::prelude::*;
 use macroquad::window::clear_background;
-use std::collections::HashMap;
+use profile::Profile;
+use session::{Attempt, Session};
+use std::env;
+use std::path::PathBuf;
+use surprise_math::{Generator, Kind, Operation, Problem, Token};
 
 const IMG_NEUTRAL_1: &str = "images/neutral_1.png";
-const IMG_NEUTRAL_2: &str = "images/neutral_2.png";
-const IMG_WHAT: &str = "images/what.png";
-const IMG_OK: &str = "images/ok.png";
-const IMG_NOK: &str = "images/nok.png";
-
-const NUM_LIMIT: i32 = 20;
-
-#[macroquad::main("surprise-math")]
-async fn main() {
-    let mut key_codes = HashMap::with_capacity(10);
-    key_codes.insert(KeyCode::Key0, 0);
-    key_codes.insert(KeyCode::Key1, 1);
-    key_codes.insert(KeyCode::Key2, 2);
-    key_codes.insert(KeyCode::Key3, 3);
-    key_codes.insert(KeyCode::Key4, 4);
-    key_codes.insert(KeyCode::Key5, 5);
-    key_codes.insert(KeyCode::Key6, 6);
-    key_codes.insert(KeyCode::Key7, 7);
-    key_codes.insert(KeyCode::Key8, 8);
-    key_codes.insert(KeyCode::Key9, 9);
+/// A streak this long, or a perfect session, makes the cat celebrate.
+const CELEBRATE_STREAK: usize = 5;
+/// Answer tiles in multiple choice, the right one and three distractors.
+const CHOICES: usize = 4;
 
+fn main() {
+    let seed = arg("--seed").and_then(|s| s.parse().ok()).unwrap_or_else(|| miniquad::date::now() as u64);
+    if let Some(count) = arg("--worksheet") {
+        write_worksheet(&count, seed);
+        return;
+    }
+    macroquad::Window::new("surprise-math", game(seed));
+}
+
+async fn game(seed: u64) {
     let neutral_1 = load_texture(IMG_NEUTRAL_1).await.unwrap();
-    let neutral_2 = load_texture(IMG_NEUTRAL_2).await.unwrap();
-    let what = load_texture(IMG_WHAT).await.unwrap();
-    let ok = load_texture(IMG_OK).await.unwrap();
-    let nok = load_texture(IMG_NOK).await.unwrap();
+    let aids = Aids::load(&neutral_1).await;
+    let mut mascot = Mascot::load(&arg("--mascot").unwrap_or_else(|| mascot::MANIFEST.to_string())).await;
+
+    let profile_name = arg("--profile").unwrap_or_else(|| profile::DEFAULT_PROFILE.to_string());
+    let mut profile = Profile::load(&profile_name);
+    let mut history = History::load(&profile);
+    let mut statistics = false;
+    if let Some(voice) = arg("--voice") {
+        profile.voice = Some(voice);
+    }
+    let mut narrator = match &profile.voice {
+        Some(pack) => Narrator::load(pack).await,
+        None => None,
+    };
+
+    let mut generator = Generator::new(seed);
+    let mut settings = Settings::new();
+    let (mut problem, mut answer) = next_problem(&mut generator, &settings, &profile);
+    let mut asked_at = 0.0;
 
-    let mut calc = Calc::new();
-    let mut answer = "".to_owned();
+    let length = arg("--problems").and_then(|n| n.parse().ok()).filter(|n| *n > 0).unwrap_or(session::SESSION_LENGTH);
+    let mut session = Session::new(length);
+    let mut report = false;
+    let mut exported: Option<String> = None;
 
     let mut game_over = false;
 
     loop {
-        rand::srand(miniquad::date::now() as u64);
         clear_background(WHITE);
 
-        if game_over {
-            let cat = if calc.equals_result(answer.as_str()) { &ok } else { &nok };
-            print_cat_image(cat);
+        if statistics {
+            history.draw();
+            if is_key_pressed(KeyCode::Tab) {
+                statistics = false;
+            }
+            next_frame().await;
+            continue;
         }
 
-        if game_over && is_key_pressed(KeyCode::Escape) {
+        if report {
+            session.draw_report();
+            if is_key_pressed(KeyCode::E) {
+                exported = Some(match session.export_csv(&profile.file_name()) {
+                    Ok(path) => format!("saved to {}", path.display()),
+                    Err(e) => format!("could not save, {}", e),
+                });
+            }
+            if let Some(message) = &exported {
+                draw_text(message.as_str(), 20.0, screen_height() - 100.0, 30.0, GRAY);
+            }
+            if is_key_pressed(KeyCode::Escape) {
+                report = false;
+                exported = None;
+                session = Session::new(length);
+                game_over = false;
+                (problem, answer) = next_problem(&mut generator, &settings, &profile);
+            }
+            next_frame().await;
+            continue;
+        }
+
+        if game_over && is_key_pressed(KeyCode::Escape) && session.is_finished() {
+            report = true;
+        } else if game_over && is_key_pressed(KeyCode::Escape) {
             game_over = false;
-            calc = Calc::new();
-            answer = "".to_string();
+            (problem, answer) = next_problem(&mut generator, &settings, &profile);
+            mascot.set(Mood::Idle, None);
         }
 
         if !game_over {
-            let cat = if get_last_key_pressed().is_none() { &neutral_1 } else { &neutral_2 };
-            print_cat_image(cat);
-
             if is_key_pressed(KeyCode::Space) {
-                match calc {
-                    _ if calc.a.is_none() => {
-                        let n = rand::gen_range(1, NUM_LIMIT);
-                        calc.a = Some(n);
+                if !problem.reveal() {
+                    mascot.set(Mood::Thinking, Some("Type your answer!".to_string()));
+                } else if problem.fully_shown() {
+                    asked_at = get_time();
+                    mascot.set(Mood::Thinking, None);
+                    if let Some(narrator) = &mut narrator {
+                        narrator.say_problem(&problem);
                     }
-                    _ if calc.operation.is_none() => {
-                        let rnd = rand::gen_range(0, 100);
-                        calc.operation = if rnd % 2 == 0 { Some(Operation::PLUS) } else { Some(Operation::SUB) };
-                    }
-                    _ if calc.b.is_none() => {
-                        let limit = calc.a.unwrap();
-                        let n = rand::gen_range(1, limit);
-                        calc.b = Some(n);
-                        calc.calculate();
-                    }
-                    _ =>
-                        print_cat_image(&what)
                 }
             }
 
-            let last_key = get_last_key_pressed();
-            if calc.not_empty() &&
-                last_key.is_some() &&
-                key_codes.contains_key(last_key.as_ref().unwrap()) {
-                let v = key_codes.get(last_key.as_ref().unwrap()).unwrap().to_string();
-                answer.push_str(v.as_str());
-            }
-        }
-
-        let card_width = screen_width() / 8.0;
-        let card_height = screen_height() / 3.0;
-        let card_gap = card_width / 4.0;
-        let card_font_size = card_width.min(card_height);
-
-        for i in 0..5 {
-            let initial_x = ((i as f32) * (card_width + card_gap)) + card_gap;
-            let screen_y = screen_height() / 2.5;
-
-            match i {
-                0 if calc.a.is_some() => {
-                    let value = format!("{}", calc.a.as_ref().unwrap());
-                    draw_text(value.as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, GOLD);
+            // operations and kinds can only change before the problem shows up
+            if problem.shown == 0 {
+                statistics = is_key_pressed(KeyCode::Tab);
+                if is_key_pressed(KeyCode::B) {
+                    blitz::play(&mut generator, &mut profile, &mut history, &settings).await;
+                    (problem, answer) = next_problem(&mut generator, &settings, &profile);
                 }
-                1 if calc.operation.is_some() => {
-                    draw_text(calc.operation.as_ref().unwrap().value().as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, BLACK);
+                if is_key_pressed(KeyCode::L) {
+                    profile.voice = next_voice(&profile.voice);
+                    profile.store();
+                    narrator = match &profile.voice {
+                        Some(pack) => Narrator::load(pack).await,
+                        None => None,
+                    };
                 }
-                2 if calc.b.is_some() => {
-                    let value = format!("{}", calc.b.as_ref().unwrap());
-                    draw_text(value.as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, GOLD);
+                if is_key_pressed(KeyCode::M) {
+                    profile.multiple_choice = !profile.multiple_choice;
+                    profile.store();
+                    (problem, answer) = next_problem(&mut generator, &settings, &profile);
                 }
-                3 => {
-                    draw_text("=", initial_x, screen_y + (card_height / 1.5), card_font_size, BLACK);
+                if is_key_pressed(KeyCode::V) {
+                    profile.visual_aids = !profile.visual_aids;
+                    profile.store();
                 }
-                4 if !answer.is_empty() => {
-                    if answer.len() == calc.result.len() {
-                        if answer == calc.result {
-                            draw_text(answer.as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, GREEN);
-                        } else {
-                            let dimensions = draw_text(answer.as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, RED);
-                            let correct = format!("({})", calc.result);
-                            draw_text(&correct, initial_x + dimensions.width, screen_y + (card_height / 1.5), card_font_size / 2.0, GREEN);
-                        }
-                        game_over = true;
-                    } else {
-                        draw_text(answer.as_str(), initial_x, screen_y + (card_height / 1.5), card_font_size, GOLD);
+                for (key, operation) in OPERATION_KEYS {
+                    if is_key_pressed(key) {
+                        settings.toggle(operation);
+                        (problem, answer) = next_problem(&mut generator, &settings, &profile);
                     }
                 }
-                i => {
-                    if i == 4 {
-                        let question = "?";
-                        draw_text(&question.repeat(calc.result.len()), initial_x, screen_y + (card_height / 1.5), card_font_size, BLACK);
-                    } else {
-                        draw_text("?", initial_x, screen_y + (card_height / 1.5), card_font_size, BLACK);
+                for (key, kind) in KIND_KEYS {
+                    if is_key_pressed(key) {
+                        settings.toggle_kind(kind);
+                        (problem, answer) = next_problem(&mut generator, &settings, &profile);
                     }
                 }
             }
+
+            if problem.fully_shown() && answer.update() {
+                let seconds = (get_time() - asked_at) as f32;
+                let correct = record_answer(&mut profile, &mut history, &problem, answer.text(), seconds);
+                session.record(Attempt {
+                    question: problem.question(),
+                    answer: answer.text().to_string(),
+                    result: problem.result(),
+                    correct,
+                    seconds,
+                });
+                if let Some(narrator) = &mut narrator {
+                    narrator.say_result(correct);
+                }
+                let perfect = session.is_finished() && session.score() == session.length;
+                if correct && (perfect || session.streak.is_multiple_of(CELEBRATE_STREAK)) {
+                    mascot.set(Mood::Celebrating, Some(format!("{} in a row!", session.streak)));
+                } else if correct {
+                    mascot.set(Mood::Happy, Some("Yes!".to_string()));
+                } else {
+                    mascot.set(Mood::Sad, Some(format!("It's {}", problem.result())));
+                }
+                game_over = true;
+            }
+        }
+
+        if let Some(narrator) = &mut narrator {
+            narrator.update();
+        }
+        mascot.update(get_last_key_pressed().is_some());
+        mascot.draw();
+        draw_problem(&problem, &answer, game_over);
+
+        if profile.visual_aids && problem.fully_shown() {
+            let top = if answer.has_tiles() { answer::tiles_bottom() + 10.0 } else { screen_height() * 0.66 };
+            aids.draw(&problem, top);
+        }
+
+        if problem.fully_shown() && !game_over {
+            answer.draw_tiles();
+            let hint = if answer.has_tiles() {
+                "Click or touch the answer, or press its number"
+            } else if problem.wants_relation() {
+                "Type < > or =, Enter to check"
+            } else {
+                "Enter to check, Backspace to fix"
+            };
+            draw_text(hint, 20.0, screen_height() - 20.0, 30.0, GRAY);
+        }
+        if game_over {
+            let next = if session.is_finished() { "Escape for the report card" } else { "Escape for the next one" };
+            draw_text(next, 20.0, screen_height() - 20.0, 30.0, GRAY);
+        }
+        session.draw_progress();
+
+        if problem.shown == 0 {
+            settings.draw();
+            let level = format!("{}: numbers up to {}, tables up to {}   (B blitz, Tab statistics, V pictures {}, M tiles {})", profile.name, profile.model.limit, profile.model.tables, on_off(profile.visual_aids), on_off(profile.multiple_choice));
+            draw_text(level.as_str(), 20.0, screen_height() - 60.0, 30.0, GRAY);
+            let voice = narrator.as_ref().map_or("off".to_string(), |n| n.title.clone());
+            draw_text(format!("L voice: {}   seed {}", voice, generator.seed).as_str(), 20.0, screen_height() - 140.0, 30.0, GRAY);
         }
 
         next_frame().await;
     }
 }
 
-fn print_cat_image(img_target: &Texture2D) {
-    draw_texture(img_target, screen_width() / 2.5, 10.0, WHITE)
+/// Lays the tokens out as cards, the unknown shows what is typed and,
+/// once `checked`, whether it was right.
+fn draw_problem(problem: &Problem, answer: &AnswerInput, checked: bool) {
+    // as many slots as the problem has tokens, five fill the width like before
+    let slots: f32 = problem.tokens.iter().map(card_slots).sum();
+    let card_width = screen_width() * 0.8 / (slots * 1.25 + 0.25);
+    let card_height = screen_height() / 3.0;
+    let card_gap = card_width / 4.0;
+    let card_font_size = card_width.min(card_height);
+
+    let mut initial_x = card_gap;
+    for (i, token) in problem.tokens.iter().enumerate() {
+        let x = initial_x;
+        let width = card_slots(token) * (card_width + card_gap);
+        initial_x += width;
+        let screen_y = screen_height() / 2.5;
+        // fractions and decimals are longer than a digit or two
+        let fit = |text: &str, font_size: f32| {
+            let measured = measure_text(text, None, font_size as u16, 1.0).width;
+            if measured > width { font_size * width / measured } else { font_size }
+        };
+
+        if !problem.is_visible(i) {
+            continue;
+        }
+        let baseline = screen_y + (card_height / 1.5);
+        match token {
+            Token::Number(_) | Token::Fraction(..) | Token::Decimal(_) => {
+                draw_text(token.value().as_str(), x, baseline, fit(&token.value(), card_font_size), GOLD);
+            }
+            Token::Operation(_) | Token::Relation(_) | Token::Digit(_) => {
+                draw_text(token.value().as_str(), x, baseline, fit(&token.value(), card_font_size), BLACK);
+            }
+            Token::Unknown if checked => {
+                let font_size = fit(answer.text(), card_font_size);
+                if problem.check(answer.text()) {
+                    draw_text(answer.text(), x, baseline, font_size, GREEN);
+                } else {
+                    let dimensions = draw_text(answer.text(), x, baseline, font_size, RED);
+                    let correct = format!("({})", problem.result());
+                    draw_text(&correct, x + dimensions.width, baseline, font_size / 2.0, GREEN);
+                }
+            }
+            Token::Unknown if !answer.is_empty() => {
+                draw_text(answer.text(), x, baseline, fit(answer.text(), card_font_size), GOLD);
+            }
+            Token::Unknown => {
+                // a single "?" so the number of digits is not given away
+                draw_text("?", x, baseline, card_font_size, BLACK);
+            }
+        }
+    }
+}
+
+/// Cards a token takes, words like `tens digit of` need more room.
+fn card_slots(token: &Token) -> f32 {
+    match token {
+        Token::Digit(_) => 3.0,
+        _ => 1.0,
+    }
+}
+
+/// Checks the answer and lets the adaptive model and the history know.
+fn record_answer(profile: &mut Profile, history: &mut History, problem: &Problem, answer: &str, seconds: f32) -> bool {
+    let (a, operation, b) = problem.fact;
+    let correct = problem.check(answer);
+    profile.model.record(problem.skill(), correct, seconds);
+    profile.store();
+    history.append(Entry {
+        time: miniquad::date::now(),
+        question: problem.question(),
+        kind: problem.kind,
+        a,
+        operation,
+        b,
+        answer: answer.to_string(),
+        correct,
+        seconds,
+    });
+    correct
+}
+
+/// The voice pack after `current` among the installed ones, then none.
+fn next_voice(current: &Option<String>) -> Option<String> {
+    let packs = narration::packs();
+    match current.as_ref().and_then(|c| packs.iter().position(|p| p == c)) {
+        Some(i) => packs.get(i + 1).cloned(),
+        None if current.is_some() => None,
+        None => packs.first().cloned(),
+    }
 }
 
-struct Calc {
-    a: Option<i32>,
-    b: Option<i32>,
-    operation: Option<Operation>,
-    result: String,
+/// `--worksheet 20 --seed 7 --ops +-x --out sheet.svg` prints problems
+/// instead of starting the game, at the level of `--profile` when given.
+fn write_worksheet(count: &str, seed: u64) {
+    let Ok(count) = count.parse::<usize>() else {
+        eprintln!("--worksheet needs a number of problems, not {}", count);
+        return;
+    };
+    let operations: Vec<Operation> = arg("--ops")
+        .map(|ops| ops.chars().filter_map(Operation::from_symbol).collect::<Vec<Operation>>())
+        .filter(|ops| !ops.is_empty())
+        .unwrap_or_else(|| Settings::new().operations);
+    let profile = arg("--profile").map(|name| Profile::load(&name)).unwrap_or_default();
+
+    let mut generator = Generator::new(seed);
+    let problems: Vec<Problem> = (0..count).map(|_| generator.problem(&operations, &[Kind::Basic], &profile.model)).collect();
+    let path = PathBuf::from(arg("--out").unwrap_or_else(|| format!("worksheet-{}.txt", seed)));
+    match worksheet::write(&path, &problems, seed) {
+        Ok(()) => println!("wrote {} problems to {}", count, path.display()),
+        Err(e) => eprintln!("could not write {}, {}", path.display(), e),
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+/// Value following `name` on the command line, as in `--profile anna`.
+fn arg(name: &str) -> Option<String> {
+    env::args().skip_while(|a| a != name).nth(1)
+}
+
+const OPERATION_KEYS: [(KeyCode, Operation); 4] = [
+    (KeyCode::F1, Operation::Plus),
+    (KeyCode::F2, Operation::Sub),
+    (KeyCode::F3, Operation::Mul),
+    (KeyCode::F4, Operation::Div),
+];
+
+const KIND_KEYS: [(KeyCode, Kind); 7] = [
+    (KeyCode::F5, Kind::Basic),
+    (KeyCode::F6, Kind::Missing),
+    (KeyCode::F7, Kind::ThreeTerms),
+    (KeyCode::F8, Kind::Compare),
+    (KeyCode::F9, Kind::Fractions),
+    (KeyCode::F10, Kind::Decimals),
+    (KeyCode::F11, Kind::PlaceValue),
+];
+
+struct Settings {
+    operations: Vec<Operation>,
+    kinds: Vec<Kind>,
 }
 
-impl Calc {
+impl Settings {
     fn new() -> Self {
-        Calc {
-            a: None,
-            b: None,
-            operation: None,
-            result: "".to_string(),
+        Settings {
+            operations: vec![Operation::Plus, Operation::Sub],
+            kinds: vec![Kind::Basic],
         }
     }
 
-    fn equals_result(&self, answer: &str) -> bool {
-        self.result == answer
+    /// Turns an operation on or off, one always stays on.
+    fn toggle(&mut self, operation: Operation) {
+        if !self.operations.contains(&operation) {
+            self.operations.push(operation);
+        } else if self.operations.len() > 1 {
+            self.operations.retain(|o| *o != operation);
+        }
     }
 
-    fn not_empty(&self) -> bool {
-        self.a.is_some() && self.operation.is_some() && self.b.is_some()
+    /// The enabled operations in key order, the same set always looks the same.
+    fn operation_set(&self) -> Vec<Operation> {
+        OPERATION_KEYS.iter().map(|(_, o)| *o).filter(|o| self.operations.contains(o)).collect()
     }
 
-    fn calculate(&mut self) {
-        match self.operation.as_ref().unwrap() {
-            Operation::PLUS => {
-                self.result = (self.a.unwrap() + self.b.unwrap()).to_string();
-            }
-            Operation::SUB => {
-                self.result = (self.a.unwrap() - self.b.unwrap()).to_string();
-            }
+    /// Turns a kind of problem on or off, one always stays on.
+    fn toggle_kind(&mut self, kind: Kind) {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        } else if self.kinds.len() > 1 {
+            self.kinds.retain(|k| *k != kind);
         }
     }
-}
 
-enum Operation {
-    PLUS,
-    SUB,
-}
+    fn draw(&self) {
+        let line = OPERATION_KEYS.iter()
+            .map(|(key, operation)| {
+                let state = if self.operations.contains(operation) { "on" } else { "off" };
+                format!("{:?} {} {}", key, operation.value(), state)
+            })
+            .collect::<Vec<String>>()
+            .join("    ");
+        draw_text(line.as_str(), 20.0, screen_height() - 20.0, 30.0, GRAY);
 
-impl Operation {
-    fn value(&self) -> String {
-        match self {
-            Operation::PLUS => "+".to_string(),
-            Operation::SUB => "-".to_string()
-        }
+        let line = KIND_KEYS.iter()
+            .map(|(key, kind)| {
+                let state = if self.kinds.contains(kind) { "on" } else { "off" };
+                format!("{:?} {} {}", key, kind.value(), state)
+            })
+            .collect::<Vec<String>>()
+            .join("    ");
+        // seven kinds do not fit a small window at full size
+        let width = measure_text(line.as_str(), None, 30, 1.0).width;
+        let font_size = if width > screen_width() - 40.0 { 30.0 * (screen_width() - 40.0) / width } else { 30.0 };
+        draw_text(line.as_str(), 20.0, screen_height() - 100.0, font_size, GRAY);
     }
 }
+
+/// A new problem of one of the enabled kinds, with an input that fits its answer.
+fn next_problem(generator: &mut Generator, settings: &Settings, profile: &Profile) -> (Problem, AnswerInput) {
+    let problem = generator.problem(&settings.operations, &settings.kinds, &profile.model);
+    let answer = if profile.multiple_choice {
+        AnswerInput::tiles(generator.choices(&problem, CHOICES).iter().map(Token::value).collect())
+    } else if problem.wants_relation() {
+        AnswerInput::relation()
+    } else {
+        AnswerInput::new()
+    };
+    (problem, answer)
+}
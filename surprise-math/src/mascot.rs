@@ -0,0 +1,222 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+
+pub const MANIFEST: &str = "images/mascot.json";
+/// Used when the manifest on disk is missing or broken.
+const DEFAULT_MANIFEST: &str = include_str!("../images/mascot.json");
+
+/// Seconds each frame of a mood stays up before the next one.
+const FRAME_FOR: f32 = 0.6;
+/// Happy and sad calm down to idle after this long.
+const REACTION_FOR: f32 = 3.0;
+const CELEBRATION_FOR: f32 = 3.0;
+/// Thinking without a key being pressed for this long drifts back to idle.
+const THINKING_FOR: f32 = 8.0;
+/// The pop in size when the mood changes.
+const POP_FOR: f32 = 0.3;
+const POP_SCALE: f32 = 0.15;
+const BOUNCE_HEIGHT: f32 = 30.0;
+const BUBBLE_FONT: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mood {
+    Idle,
+    Thinking,
+    Happy,
+    Sad,
+    Celebrating,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    idle: Vec<Sprite>,
+    thinking: Vec<Sprite>,
+    happy: Vec<Sprite>,
+    sad: Vec<Sprite>,
+    celebrating: Vec<Sprite>,
+}
+
+/// An image, or part of a sprite sheet when `rect` (x, y, width, height) is given.
+#[derive(Deserialize)]
+struct Sprite {
+    image: String,
+    #[serde(default)]
+    rect: Option<[f32; 4]>,
+}
+
+struct Frame {
+    texture: Texture2D,
+    source: Option<Rect>,
+}
+
+impl Frame {
+    fn size(&self) -> Vec2 {
+        self.source.map_or(self.texture.size(), |r| r.size())
+    }
+}
+
+/// The cat reacting to the child: a mood with its own frames, timed moves
+/// from one mood to the next, a bounce and a pop on every change and an
+/// optional speech bubble. The sprites come from a JSON manifest, see
+/// `images/mascot.json` and `images/mascot-emoji.json`.
+pub struct Mascot {
+    frames: HashMap<Mood, Vec<Frame>>,
+    mood: Mood,
+    since: f64,
+    bubble: Option<String>,
+}
+
+impl Mascot {
+    pub async fn load(path: &str) -> Self {
+        let manifest = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str::<Manifest>(&raw).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                eprintln!("using the default mascot, {}: {}", path, e);
+                serde_json::from_str(DEFAULT_MANIFEST).unwrap()
+            });
+
+        let mut frames = load_frames(manifest).await;
+        // a mood without a single image would hide the cat whenever it comes up
+        if let Some(mood) = [Mood::Idle, Mood::Thinking, Mood::Happy, Mood::Sad, Mood::Celebrating].into_iter().find(|m| frames[m].is_empty()) {
+            eprintln!("using the default mascot, {}: no images for {:?}", path, mood);
+            frames = load_frames(serde_json::from_str(DEFAULT_MANIFEST).unwrap()).await;
+        }
+
+        Mascot {
+            frames,
+            mood: Mood::Idle,
+            since: get_time(),
+            bubble: None,
+        }
+    }
+
+    /// Changes the mood and what the cat says, restarting the animation
+    /// only when the mood really changes.
+    pub fn set(&mut self, mood: Mood, bubble: Option<String>) {
+        if mood != self.mood {
+            self.mood = mood;
+            self.since = get_time();
+        }
+        self.bubble = bubble;
+    }
+
+    /// Moves on to the next mood once the current one has played out.
+    /// `busy` keeps a thinking cat thinking.
+    pub fn update(&mut self, busy: bool) {
+        if busy && self.mood == Mood::Thinking {
+            self.since = get_time();
+        }
+        let elapsed = self.elapsed();
+        match self.mood {
+            Mood::Celebrating if elapsed > CELEBRATION_FOR => self.set(Mood::Happy, self.bubble.clone()),
+            Mood::Happy | Mood::Sad if elapsed > REACTION_FOR => self.set(Mood::Idle, None),
+            Mood::Thinking if elapsed > THINKING_FOR => self.set(Mood::Idle, None),
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self) {
+        let elapsed = self.elapsed();
+        let Some(frame) = self.frame(elapsed) else {
+            return;
+        };
+
+        // a pop right after the change, then the mood's own move
+        let pop = 1.0 + POP_SCALE * (1.0 - elapsed / POP_FOR).max(0.0);
+        let (lift, scale, rotation) = match self.mood {
+            Mood::Idle => (0.0, 1.0 + 0.02 * (elapsed * 2.0).sin(), 0.0),
+            Mood::Thinking => (0.0, 1.0, 0.08 * (elapsed * 1.5).sin()),
+            Mood::Happy => (BOUNCE_HEIGHT * bounce(elapsed, 1.5), 1.0, 0.0),
+            Mood::Sad => (-10.0 * (elapsed / 0.5).min(1.0), 0.95, 0.0),
+            Mood::Celebrating => (BOUNCE_HEIGHT * 1.5 * (elapsed * 2.0 * PI).sin().abs(), 1.0, 0.15 * (elapsed * 3.0).sin()),
+        };
+
+        let size = frame.size() * scale * pop;
+        let base = frame.size();
+        // keep the cat centred where it used to stand
+        let x = screen_width() / 2.5 + (base.x - size.x) / 2.0;
+        let y = 10.0 + (base.y - size.y) / 2.0 - lift;
+        let params = DrawTextureParams {
+            dest_size: Some(size),
+            source: frame.source,
+            rotation,
+            ..Default::default()
+        };
+        draw_texture_ex(&frame.texture, x, y, WHITE, params);
+
+        if let Some(text) = &self.bubble {
+            draw_bubble(text, x + size.x, y + size.y * 0.3);
+        }
+    }
+
+    fn frame(&self, elapsed: f32) -> Option<&Frame> {
+        let frames = &self.frames[&self.mood];
+        if frames.is_empty() {
+            // a manifest with no sprites for this mood still shows a cat
+            return self.frames[&Mood::Idle].first();
+        }
+        frames.get((elapsed / FRAME_FOR) as usize % frames.len())
+    }
+
+    fn elapsed(&self) -> f32 {
+        (get_time() - self.since) as f32
+    }
+}
+
+/// Bounces that die down over `length` seconds, 0.0 to 1.0.
+fn bounce(elapsed: f32, length: f32) -> f32 {
+    let left = (1.0 - elapsed / length).max(0.0);
+    (elapsed * 3.0 * PI).sin().abs() * left
+}
+
+fn draw_bubble(text: &str, x: f32, y: f32) {
+    let dimensions = measure_text(text, None, BUBBLE_FONT as u16, 1.0);
+    let padding = 12.0;
+    let (left, top) = (x + 20.0, y - dimensions.height - padding);
+    let (width, height) = (dimensions.width + 2.0 * padding, dimensions.height + 2.0 * padding);
+
+    draw_rectangle(left, top, width, height, WHITE);
+    draw_rectangle_lines(left, top, width, height, 3.0, DARKGRAY);
+    // the tail covers the border where it joins the bubble
+    draw_triangle(vec2(x, y + 10.0), vec2(left + 2.0, top + height / 2.0 - 10.0), vec2(left + 2.0, top + height / 2.0 + 10.0), WHITE);
+    draw_line(x, y + 10.0, left, top + height / 2.0 - 10.0, 3.0, DARKGRAY);
+    draw_line(x, y + 10.0, left, top + height / 2.0 + 10.0, 3.0, DARKGRAY);
+    draw_text(text, left + padding, top + padding + dimensions.offset_y, BUBBLE_FONT, BLACK);
+}
+
+/// The frames of every mood, skipping images that do not load.
+async fn load_frames(manifest: Manifest) -> HashMap<Mood, Vec<Frame>> {
+    let mut textures: HashMap<String, Texture2D> = HashMap::new();
+    let mut frames = HashMap::new();
+    let moods = [
+        (Mood::Idle, manifest.idle),
+        (Mood::Thinking, manifest.thinking),
+        (Mood::Happy, manifest.happy),
+        (Mood::Sad, manifest.sad),
+        (Mood::Celebrating, manifest.celebrating),
+    ];
+    for (mood, sprites) in moods {
+        let mut loaded = vec![];
+        for sprite in sprites {
+            if !textures.contains_key(&sprite.image) {
+                match load_texture(&sprite.image).await {
+                    Ok(texture) => textures.insert(sprite.image.clone(), texture),
+                    Err(e) => {
+                        eprintln!("skipping {}, {}", sprite.image, e);
+                        continue;
+                    }
+                };
+            }
+            loaded.push(Frame {
+                texture: textures[&sprite.image].clone(),
+                source: sprite.rect.map(|[x, y, w, h]| Rect::new(x, y, w, h)),
+            });
+        }
+        frames.insert(mood, loaded);
+    }
+    frames
+}
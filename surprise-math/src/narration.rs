@@ -0,0 +1,203 @@
+use macroquad::audio::{load_sound, play_sound_once, stop_sound, Sound};
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use surprise_math::{Problem, Token};
+
+const VOICES_DIR: &str = "voices";
+const MANIFEST: &str = "voice.json";
+/// Silence between two clips, so the words do not run together.
+const GAP: f32 = 0.1;
+
+/// `voices/<pack>/voice.json`: a clip file for each word, relative to the
+/// pack, see `voices/README.md` for recording one. Numbers are looked up
+/// whole first, so a pack can record "27" when its language does not say
+/// it as "20" and "7".
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    language: String,
+    /// length of clips that do not give their own
+    seconds: f32,
+    clips: HashMap<String, Clip>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Clip {
+    File(String),
+    Timed { file: String, seconds: f32 },
+}
+
+/// Reads problems out loud from recorded clips, one clip after the other.
+/// Missing packs and clips leave it quiet rather than stopping the game.
+pub struct Narrator {
+    pub title: String,
+    clips: HashMap<String, (Sound, f32)>,
+    queue: VecDeque<(Sound, f32)>,
+    playing: Option<Sound>,
+    next_at: f64,
+}
+
+impl Narrator {
+    pub async fn load(pack: &str) -> Option<Self> {
+        let dir = PathBuf::from(VOICES_DIR).join(pack);
+        let manifest: Manifest = match fs::read_to_string(dir.join(MANIFEST))
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+        {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("no voice {}, {}", pack, e);
+                return None;
+            }
+        };
+
+        let mut clips = HashMap::new();
+        let mut missing = 0;
+        let listed = manifest.clips.len();
+        for (word, clip) in manifest.clips {
+            let (file, seconds) = match clip {
+                Clip::File(file) => (file, manifest.seconds),
+                Clip::Timed { file, seconds } => (file, seconds),
+            };
+            // a pack still being recorded, said once below rather than per clip
+            let path = dir.join(file);
+            if !path.exists() {
+                missing += 1;
+                continue;
+            }
+            if let Some(sound) = try_load(&path).await {
+                clips.insert(word, (sound, seconds));
+            }
+        }
+        if missing > 0 {
+            eprintln!("voice {} is missing {} of {} clips", pack, missing, listed);
+        }
+        if clips.is_empty() {
+            eprintln!("no voice {}, none of its clips could be played", pack);
+            return None;
+        }
+
+        Some(Narrator {
+            title: format!("{} ({})", manifest.name, manifest.language),
+            clips,
+            queue: VecDeque::new(),
+            playing: None,
+            next_at: 0.0,
+        })
+    }
+
+    /// Reads the problem, the unknown as "what".
+    pub fn say_problem(&mut self, problem: &Problem) {
+        let words: Vec<String> = problem.tokens.iter()
+            .flat_map(|token| match token {
+                Token::Number(n) | Token::Decimal(n) if n.is_integer() => self.number_words(n.num() as i32),
+                Token::Fraction(num, den) => self.fraction_words(*num, *den),
+                Token::Number(n) => self.fraction_words(n.num() as i32, n.den() as i32),
+                Token::Decimal(_) => self.decimal_words(&token.value()),
+                Token::Digit(place) => vec![place.value().to_string(), "digit_of".to_string()],
+                _ => vec![token.value()],
+            })
+            .collect();
+        self.say(&words);
+    }
+
+    pub fn say_result(&mut self, correct: bool) {
+        self.say(&[if correct { "correct" } else { "try_again" }.to_string()]);
+    }
+
+    /// Clip names for a number, the whole number when the pack recorded it,
+    /// otherwise built the English way: 127 is "1", "hundred", "20", "7".
+    fn number_words(&self, n: i32) -> Vec<String> {
+        if self.clips.contains_key(&n.to_string()) || (0..20).contains(&n) {
+            return vec![n.to_string()];
+        }
+        if n < 0 {
+            return ["-".to_string()].into_iter().chain(self.number_words(-n)).collect();
+        }
+        if n >= 100 {
+            let mut words = self.number_words(n / 100);
+            words.push("hundred".to_string());
+            if n % 100 != 0 {
+                words.extend(self.number_words(n % 100));
+            }
+            return words;
+        }
+        let mut words = vec![(n / 10 * 10).to_string()];
+        if n % 10 != 0 {
+            words.push((n % 10).to_string());
+        }
+        words
+    }
+
+    /// "3", "over", "4" for three quarters.
+    fn fraction_words(&self, num: i32, den: i32) -> Vec<String> {
+        let mut words = self.number_words(num);
+        words.push("over".to_string());
+        words.extend(self.number_words(den));
+        words
+    }
+
+    /// "1", "point", "2", "5" for 1.25, the digits after the point one by one.
+    fn decimal_words(&self, text: &str) -> Vec<String> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let mut words = self.number_words(whole.parse().unwrap_or(0));
+        if !fraction.is_empty() {
+            words.push("point".to_string());
+            words.extend(fraction.chars().map(|c| c.to_string()));
+        }
+        words
+    }
+
+    /// Drops whatever is still being said and queues the words.
+    fn say(&mut self, words: &[String]) {
+        if let Some(sound) = self.playing.take() {
+            stop_sound(&sound);
+        }
+        self.queue = words.iter().filter_map(|w| self.clips.get(w).cloned()).collect();
+        self.next_at = get_time();
+    }
+
+    /// Starts the next clip once the previous one has finished.
+    pub fn update(&mut self) {
+        let now = get_time();
+        if now < self.next_at {
+            return;
+        }
+        if let Some((sound, seconds)) = self.queue.pop_front() {
+            play_sound_once(&sound);
+            self.playing = Some(sound);
+            self.next_at = now + (seconds + GAP) as f64;
+        } else {
+            self.playing = None;
+        }
+    }
+}
+
+/// The installed packs, sorted, as the names used on the command line.
+pub fn packs() -> Vec<String> {
+    let mut packs: Vec<String> = fs::read_dir(VOICES_DIR)
+        .map(|dir| {
+            dir.filter_map(|e| e.ok())
+                .filter(|e| e.path().join(MANIFEST).exists())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort();
+    packs
+}
+
+async fn try_load(path: &Path) -> Option<Sound> {
+    let path = path.display().to_string();
+    match load_sound(&path).await {
+        Ok(sound) => Some(sound),
+        Err(e) => {
+            eprintln!("narrating without {}, {}", path, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use crate::adaptive::Skill;
+use crate::rational::Rational;
+use crate::Operation;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Largest number added or taken away by the third term of a three-term problem.
+const THIRD_TERM_LIMIT: i32 = 10;
+/// How far the right side of a comparison may be from the true value.
+const COMPARE_SPREAD: i32 = 2;
+/// Largest denominator of a fraction sum.
+const DENOMINATOR_LIMIT: i32 = 12;
+/// Largest term of a decimal sum, in tenths.
+const TENTHS_LIMIT: i32 = 99;
+/// Place value questions use numbers up to this one.
+const PLACE_VALUE_LIMIT: i32 = 999;
+
+/// The shapes a problem can take.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Kind {
+    /// `7 + 5 = ?`
+    #[default]
+    Basic,
+    /// `7 + ? = 12`
+    Missing,
+    /// `3 + 4 - 2 = ?`
+    ThreeTerms,
+    /// `3 + 4 ? 8`, answered with `<`, `>` or `=`
+    Compare,
+    /// `1/5 + 2/5 = ?`, always the same denominator
+    Fractions,
+    /// `0.4 + 1.3 = ?`
+    Decimals,
+    /// `tens digit of 47 = ?`
+    PlaceValue,
+}
+
+impl Kind {
+    pub fn value(&self) -> &'static str {
+        match self {
+            Kind::Basic => "a+b",
+            Kind::Missing => "a+?",
+            Kind::ThreeTerms => "a+b-c",
+            Kind::Compare => "<>",
+            Kind::Fractions => "a/n+b/n",
+            Kind::Decimals => "0.a+0.b",
+            Kind::PlaceValue => "tens",
+        }
+    }
+
+    /// Whether the problem is built on the `a op b` fact the model picks.
+    /// Fractions, decimals and places only keep a stand-in for it.
+    pub fn uses_fact(&self) -> bool {
+        !matches!(self, Kind::Fractions | Kind::Decimals | Kind::PlaceValue)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    Less,
+    Greater,
+    Equal,
+}
+
+impl Relation {
+    pub fn value(&self) -> &'static str {
+        match self {
+            Relation::Less => "<",
+            Relation::Greater => ">",
+            Relation::Equal => "=",
+        }
+    }
+
+    fn between(left: Rational, right: Rational) -> Self {
+        if left < right {
+            Relation::Less
+        } else if left > right {
+            Relation::Greater
+        } else {
+            Relation::Equal
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        [Relation::Less, Relation::Greater, Relation::Equal].into_iter().find(|r| r.value() == text)
+    }
+}
+
+/// A digit of a whole number, by the power of ten it stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Place {
+    Ones,
+    Tens,
+    Hundreds,
+}
+
+impl Place {
+    pub fn value(&self) -> &'static str {
+        match self {
+            Place::Ones => "ones",
+            Place::Tens => "tens",
+            Place::Hundreds => "hundreds",
+        }
+    }
+
+    pub fn power(&self) -> i32 {
+        match self {
+            Place::Ones => 1,
+            Place::Tens => 10,
+            Place::Hundreds => 100,
+        }
+    }
+
+    /// The digit at this place, `None` for a number that is not whole or
+    /// not positive.
+    fn digit(&self, n: Rational) -> Option<Rational> {
+        if !n.is_integer() || n.num() < 0 {
+            return None;
+        }
+        Some(Rational::integer(n.num() / self.power() as i64 % 10))
+    }
+}
+
+/// One slot of a problem as it is laid out on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    /// a whole number, or a fraction in lowest terms
+    Number(Rational),
+    /// a fraction as written, `2/6` stays `2/6`
+    Fraction(i32, i32),
+    /// a number written with a decimal point when it has one
+    Decimal(Rational),
+    Operation(Operation),
+    Relation(Relation),
+    /// `tens digit of`, takes the number after it
+    Digit(Place),
+    /// what the child has to find
+    Unknown,
+}
+
+impl Token {
+    pub fn value(&self) -> String {
+        match self {
+            Token::Number(n) => n.to_string(),
+            Token::Fraction(num, den) => format!("{}/{}", num, den),
+            Token::Decimal(n) => n.to_decimal().unwrap_or_else(|| n.to_string()),
+            Token::Operation(operation) => operation.value(),
+            Token::Relation(relation) => relation.value().to_string(),
+            Token::Digit(place) => format!("{} digit of", place.value()),
+            Token::Unknown => "?".to_string(),
+        }
+    }
+
+    /// The number a number token stands for, however it is written.
+    pub fn number(&self) -> Option<Rational> {
+        match self {
+            Token::Number(n) | Token::Decimal(n) => Some(*n),
+            Token::Fraction(num, den) => Rational::new(*num as i64, *den as i64),
+            _ => None,
+        }
+    }
+
+    /// A token written like this one for another number, so a wrong
+    /// answer to a decimal sum is a decimal too.
+    pub fn with_number(&self, n: Rational) -> Token {
+        match self {
+            Token::Decimal(_) => Token::Decimal(n),
+            Token::Fraction(_, den) if (*den as i64) % n.den() == 0 => {
+                let num = n.num() * (*den as i64 / n.den());
+                Token::Fraction(num as i32, *den)
+            }
+            _ => Token::Number(n),
+        }
+    }
+}
+
+fn number(n: i32) -> Token {
+    Token::Number(n.into())
+}
+
+/// A problem is a row of tokens with exactly one relation and one unknown.
+/// It is built around a single fact `a op b`, the part the adaptive model
+/// tracks, and checked by evaluating both sides with the answer filled in.
+/// Answers are exact, so `3/4`, `6/8` and `0.75` are all right for the same
+/// problem.
+pub struct Problem {
+    pub kind: Kind,
+    pub tokens: Vec<Token>,
+    /// the fact the problem is built around: numerators for fractions,
+    /// tenths for decimals and `n ÷ 10ⁿ` for the place of a digit
+    pub fact: (i32, Operation, i32),
+    /// what goes in place of the unknown
+    pub solution: Token,
+    /// tokens up to this one are on screen, the unknown always is
+    pub shown: usize,
+}
+
+impl Problem {
+    pub fn new(kind: Kind, fact: (i32, Operation, i32), rng: &mut impl Rng) -> Self {
+        let (a, operation, b) = fact;
+        let c = operation.apply(a, b);
+        let op = Token::Operation(operation);
+        let equals = Token::Relation(Relation::Equal);
+
+        let (tokens, solution) = match kind {
+            Kind::Basic => (vec![number(a), op, number(b), equals, Token::Unknown], number(c)),
+            Kind::Missing if rng.random_bool(0.5) => (vec![Token::Unknown, op, number(b), equals, number(c)], number(a)),
+            Kind::Missing => (vec![number(a), op, Token::Unknown, equals, number(c)], number(b)),
+            Kind::ThreeTerms => {
+                // never go below zero
+                let (third, d) = if c > 1 && rng.random_bool(0.5) {
+                    (Operation::Sub, rng.random_range(1..c))
+                } else {
+                    (Operation::Plus, rng.random_range(1..THIRD_TERM_LIMIT))
+                };
+                let tokens = vec![number(a), op, number(b), Token::Operation(third), number(d), equals, Token::Unknown];
+                (tokens, number(third.apply(c, d)))
+            }
+            Kind::Compare => {
+                let right = (c + rng.random_range(-COMPARE_SPREAD..=COMPARE_SPREAD)).max(0);
+                let tokens = vec![number(a), op, number(b), Token::Unknown, number(right)];
+                (tokens, Token::Relation(Relation::between(c.into(), right.into())))
+            }
+            Kind::Fractions | Kind::Decimals | Kind::PlaceValue => return Problem::with_numbers(kind, rng),
+        };
+
+        Problem {
+            kind,
+            tokens,
+            fact,
+            solution,
+            shown: 0,
+        }
+    }
+
+    /// A fraction sum, a decimal sum or a place value question. These do
+    /// not use the fact the model picks, only the kind.
+    pub fn with_numbers(kind: Kind, rng: &mut impl Rng) -> Self {
+        let plus = Token::Operation(Operation::Plus);
+        let equals = Token::Relation(Relation::Equal);
+        let tenths = |n: i32| Rational::new(n as i64, 10).expect("ten is not zero");
+
+        let (tokens, fact, solution) = match kind {
+            Kind::Fractions => {
+                let den = rng.random_range(2..=DENOMINATOR_LIMIT);
+                let (a, b) = (rng.random_range(1..den), rng.random_range(1..den));
+                let tokens = vec![Token::Fraction(a, den), plus, Token::Fraction(b, den), equals, Token::Unknown];
+                (tokens, (a, Operation::Plus, b), Token::Fraction(a + b, den))
+            }
+            Kind::Decimals => {
+                let (a, b) = (rng.random_range(1..=TENTHS_LIMIT), rng.random_range(1..=TENTHS_LIMIT));
+                let tokens = vec![Token::Decimal(tenths(a)), plus, Token::Decimal(tenths(b)), equals, Token::Unknown];
+                (tokens, (a, Operation::Plus, b), Token::Decimal(tenths(a + b)))
+            }
+            _ => {
+                let n = rng.random_range(10..=PLACE_VALUE_LIMIT);
+                let places: &[Place] = if n >= 100 { &[Place::Ones, Place::Tens, Place::Hundreds] } else { &[Place::Ones, Place::Tens] };
+                let place = *places.choose(rng).expect("there are places to pick");
+                let tokens = vec![Token::Digit(place), number(n), equals, Token::Unknown];
+                (tokens, (n, Operation::Div, place.power()), number(n / place.power() % 10))
+            }
+        };
+
+        Problem {
+            kind,
+            tokens,
+            fact,
+            solution,
+            shown: 0,
+        }
+    }
+
+    /// The skill an answer to this problem trains.
+    pub fn skill(&self) -> Skill {
+        let (a, operation, b) = self.fact;
+        match self.kind {
+            Kind::Fractions => Skill::Fractions,
+            Kind::Decimals => Skill::Decimals,
+            Kind::PlaceValue => Skill::PlaceValue,
+            _ => Skill::of(a, operation, b),
+        }
+    }
+
+    /// Whether the answer is a comparison rather than a number.
+    pub fn wants_relation(&self) -> bool {
+        matches!(self.solution, Token::Relation(_))
+    }
+
+    pub fn question(&self) -> String {
+        self.tokens.iter().map(Token::value).collect::<Vec<String>>().join(" ")
+    }
+
+    pub fn result(&self) -> String {
+        self.solution.value()
+    }
+
+    /// Fills the answer in and checks that the relation holds.
+    pub fn check(&self, answer: &str) -> bool {
+        // "07", "-0", "6/8" and "0.75" are still right
+        let filled = match Rational::parse(answer) {
+            Some(n) => Token::Number(n),
+            None => match Relation::parse(answer) {
+                Some(relation) => Token::Relation(relation),
+                None => return false,
+            },
+        };
+        let tokens: Vec<Token> = self.tokens.iter().map(|t| if *t == Token::Unknown { filled } else { *t }).collect();
+
+        let Some(at) = tokens.iter().position(|t| matches!(t, Token::Relation(_))) else {
+            return false;
+        };
+        let Token::Relation(relation) = tokens[at] else {
+            return false;
+        };
+        match (evaluate(&tokens[..at]), evaluate(&tokens[at + 1..])) {
+            (Some(left), Some(right)) => Relation::between(left, right) == relation,
+            _ => false,
+        }
+    }
+
+    /// Shows the next token, false once all of them are shown.
+    pub fn reveal(&mut self) -> bool {
+        match (self.shown..self.tokens.len()).find(|i| self.tokens[*i] != Token::Unknown) {
+            Some(i) => {
+                self.shown = i + 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Puts the whole problem on screen at once.
+    pub fn show_all(&mut self) {
+        self.shown = self.tokens.len();
+    }
+
+    pub fn is_visible(&self, i: usize) -> bool {
+        i < self.shown || self.tokens[i] == Token::Unknown
+    }
+
+    pub fn fully_shown(&self) -> bool {
+        self.tokens[self.shown..].iter().all(|t| *t == Token::Unknown)
+    }
+}
+
+/// Value of one side of a relation, × and ÷ before + and -. `None` when
+/// the side is not a well formed expression or divides by zero.
+fn evaluate(side: &[Token]) -> Option<Rational> {
+    if let [Token::Digit(place), n] = side {
+        return place.digit(n.number()?);
+    }
+
+    let mut numbers = vec![];
+    let mut operations = vec![];
+    for (i, token) in side.iter().enumerate() {
+        match (i % 2, token.number(), token) {
+            (0, Some(n), _) => numbers.push(n),
+            (1, _, Token::Operation(operation)) => operations.push(*operation),
+            _ => return None,
+        }
+    }
+    if numbers.len() != operations.len() + 1 {
+        return None;
+    }
+
+    let mut terms = vec![numbers[0]];
+    let mut signs = vec![];
+    for (operation, n) in operations.iter().zip(&numbers[1..]) {
+        let last = terms.last_mut()?;
+        match operation {
+            Operation::Mul => *last = last.checked_mul(*n)?,
+            Operation::Div => *last = last.checked_div(*n)?,
+            Operation::Plus | Operation::Sub => {
+                signs.push(*operation);
+                terms.push(*n);
+            }
+        }
+    }
+    terms[1..].iter().zip(signs).try_fold(terms[0], |total, (n, sign)| match sign {
+        Operation::Plus => total.checked_add(*n),
+        _ => total.checked_sub(*n),
+    })
+}
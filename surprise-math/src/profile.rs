@@ -0,0 +1,91 @@
+use crate::blitz::Record;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use surprise_math::{Model, Operation};
+
+const PROFILES_DIR: &str = "profiles";
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// What the game remembers about one child between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default)]
+    pub model: Model,
+    /// draw counting aids under the problems
+    #[serde(default)]
+    pub visual_aids: bool,
+    /// pick the answer from tiles instead of typing it
+    #[serde(default)]
+    pub multiple_choice: bool,
+    /// voice pack reading the problems out loud, none when quiet
+    #[serde(default)]
+    pub voice: Option<String>,
+    #[serde(default)]
+    pub blitz_records: Vec<Record>,
+}
+
+impl Profile {
+    /// Loads the profile, or starts a fresh one when it does not exist yet
+    /// or cannot be read.
+    pub fn load(name: &str) -> Self {
+        let path = path(name, "json");
+        let mut profile = match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                eprintln!("starting {} over, {}", path.display(), e);
+                Profile::default()
+            }),
+            Err(_) => Profile::default(),
+        };
+        profile.name = name.to_string();
+        profile
+    }
+
+    pub fn store(&self) {
+        let stored = fs::create_dir_all(PROFILES_DIR)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(self).map_err(|e| e.to_string()))
+            .and_then(|raw| fs::write(path(&self.name, "json"), raw).map_err(|e| e.to_string()));
+        if let Err(e) = stored {
+            eprintln!("could not save profile {}, {}", self.name, e);
+        }
+    }
+
+    /// Best blitz score with exactly these operations, 0 before the first round.
+    pub fn blitz_record(&self, operations: &[Operation]) -> u32 {
+        self.blitz_records.iter().find(|r| r.operations == operations).map_or(0, |r| r.score)
+    }
+
+    /// Keeps the score if it beats the record, true when it does.
+    pub fn set_blitz_record(&mut self, operations: &[Operation], score: u32) -> bool {
+        if score <= self.blitz_record(operations) {
+            return false;
+        }
+        self.blitz_records.retain(|r| r.operations != operations);
+        self.blitz_records.push(Record {
+            operations: operations.to_vec(),
+            score,
+        });
+        true
+    }
+
+    /// Where every answer of this profile is appended, one JSON object per line.
+    pub fn history_path(&self) -> PathBuf {
+        path(&self.name, "history.jsonl")
+    }
+
+    /// The name as it is safe to use in a file name, `../x` becomes `___x`.
+    pub fn file_name(&self) -> String {
+        file_name(&self.name)
+    }
+}
+
+fn file_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn path(name: &str, extension: &str) -> PathBuf {
+    PathBuf::from(PROFILES_DIR).join(format!("{}.{}", file_name(name), extension))
+}
@@ -0,0 +1,154 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Most digits after the point read or written, more than any sheet shows.
+const MAX_PLACES: u32 = 9;
+
+/// An exact fraction, always in lowest terms with a positive denominator,
+/// so two equal values compare equal whatever form they were written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i64,
+    den: i64,
+}
+
+impl Rational {
+    /// `None` when `den` is zero, or either is `i64::MIN`, which has no
+    /// positive counterpart to normalise the sign with.
+    pub fn new(num: i64, den: i64) -> Option<Self> {
+        if den == 0 || num == i64::MIN || den == i64::MIN {
+            return None;
+        }
+        let divisor = gcd(num, den) * den.signum();
+        Some(Rational {
+            num: num / divisor,
+            den: den / divisor,
+        })
+    }
+
+    pub fn integer(n: i64) -> Self {
+        Rational { num: n, den: 1 }
+    }
+
+    pub fn num(&self) -> i64 {
+        self.num
+    }
+
+    pub fn den(&self) -> i64 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let num = self.num.checked_mul(other.den)?.checked_add(other.num.checked_mul(self.den)?)?;
+        Rational::new(num, self.den.checked_mul(other.den)?)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(Rational { num: other.num.checked_neg()?, den: other.den })
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        Rational::new(self.num.checked_mul(other.num)?, self.den.checked_mul(other.den)?)
+    }
+
+    /// `None` when dividing by zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        Rational::new(self.num.checked_mul(other.den)?, self.den.checked_mul(other.num)?)
+    }
+
+    /// Reads `7`, `-7`, `3/4`, `0.75`, `.5` or `1,5`, `None` for anything else.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some((num, den)) = text.split_once('/') {
+            return Rational::new(num.trim().parse().ok()?, den.trim().parse().ok()?);
+        }
+
+        let text = text.replace(',', ".");
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.as_str()),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() && fraction.is_empty() || !all_digits(whole) || !all_digits(fraction) || fraction.len() > MAX_PLACES as usize {
+            return None;
+        }
+
+        let den = 10_i64.pow(fraction.len() as u32);
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+        let fraction: i64 = if fraction.is_empty() { 0 } else { fraction.parse().ok()? };
+        let num = whole.checked_mul(den)?.checked_add(fraction)?;
+        Rational::new(if negative { -num } else { num }, den)
+    }
+
+    /// `0.75` for three quarters, `None` when the decimals never end, as for
+    /// a third, or run past what [`Rational::parse`] reads back.
+    pub fn to_decimal(&self) -> Option<String> {
+        let mut den = self.den;
+        let mut places = 0;
+        for factor in [2, 5] {
+            while den % factor == 0 {
+                den /= factor;
+            }
+        }
+        if den != 1 {
+            return None;
+        }
+        while 10_i64.pow(places) % self.den != 0 {
+            places += 1;
+            if places > MAX_PLACES {
+                return None;
+            }
+        }
+        if places == 0 {
+            return Some(self.num.to_string());
+        }
+        let scaled = self.num.checked_mul(10_i64.pow(places) / self.den)?;
+        let sign = if scaled < 0 { "-" } else { "" };
+        let scaled = scaled.abs();
+        let unit = 10_i64.pow(places);
+        Some(format!("{}{}.{:0width$}", sign, scaled / unit, scaled % unit, width = places as usize))
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Self {
+        Rational::integer(n as i64)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        (self.num as i128 * other.den as i128).cmp(&(other.num as i128 * self.den as i128))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// `7` for whole numbers, `3/4` otherwise.
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
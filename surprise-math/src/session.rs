@@ -0,0 +1,97 @@
+use macroquad::prelude::*;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub const SESSION_LENGTH: usize = 10;
+const REPORTS_DIR: &str = "reports";
+
+/// One answered problem.
+pub struct Attempt {
+    pub question: String,
+    pub answer: String,
+    pub result: String,
+    pub correct: bool,
+    pub seconds: f32,
+}
+
+/// A fixed number of problems in a row, ending on a report card.
+pub struct Session {
+    pub length: usize,
+    pub attempts: Vec<Attempt>,
+    pub streak: usize,
+    pub best_streak: usize,
+}
+
+impl Session {
+    pub fn new(length: usize) -> Self {
+        Session {
+            length,
+            attempts: Vec::with_capacity(length),
+            streak: 0,
+            best_streak: 0,
+        }
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        self.streak = if attempt.correct { self.streak + 1 } else { 0 };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.attempts.push(attempt);
+    }
+
+    pub fn score(&self) -> usize {
+        self.attempts.iter().filter(|a| a.correct).count()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.attempts.len() >= self.length
+    }
+
+    /// Progress bar along the top with the running score and streak.
+    pub fn draw_progress(&self) {
+        let done = self.attempts.len() as f32 / self.length as f32;
+        draw_rectangle(0.0, 0.0, screen_width(), 12.0, LIGHTGRAY);
+        draw_rectangle(0.0, 0.0, screen_width() * done, 12.0, GREEN);
+
+        let status = format!("{} / {}   score {}   streak {}", self.attempts.len(), self.length, self.score(), self.streak);
+        draw_text(status.as_str(), 20.0, 45.0, 30.0, DARKGRAY);
+    }
+
+    pub fn draw_report(&self) {
+        let line_height = ((screen_height() - 180.0) / (self.length as f32 + 1.0)).min(36.0);
+        let font_size = line_height * 0.9;
+        let columns = [20.0, screen_width() * 0.35, screen_width() * 0.5, screen_width() * 0.7];
+
+        draw_text("Report card", 20.0, 60.0, 60.0, GOLD);
+        let mut y = 100.0 + line_height;
+        for (x, title) in columns.iter().zip(["problem", "answer", "correct", "seconds"]) {
+            draw_text(title, *x, y, font_size, GRAY);
+        }
+        for attempt in &self.attempts {
+            y += line_height;
+            let color = if attempt.correct { DARKGREEN } else { RED };
+            draw_text(attempt.question.as_str(), columns[0], y, font_size, BLACK);
+            draw_text(attempt.answer.as_str(), columns[1], y, font_size, color);
+            draw_text(attempt.result.as_str(), columns[2], y, font_size, BLACK);
+            draw_text(format!("{:.1}", attempt.seconds).as_str(), columns[3], y, font_size, BLACK);
+        }
+
+        let summary = format!("{} of {} right, best streak {}", self.score(), self.length, self.best_streak);
+        draw_text(summary.as_str(), 20.0, screen_height() - 60.0, 36.0, DARKGREEN);
+        draw_text("E to export as CSV, Escape for a new session", 20.0, screen_height() - 20.0, 30.0, GRAY);
+    }
+
+    /// Writes the report to `reports/<profile>-<time>.csv` and returns its
+    /// path, `profile` as given by [`Profile::file_name`](crate::profile::Profile::file_name).
+    pub fn export_csv(&self, profile: &str) -> io::Result<PathBuf> {
+        let mut csv = "problem,answer,correct_answer,correct,seconds\n".to_string();
+        for a in &self.attempts {
+            csv.push_str(format!("{},{},{},{},{:.1}\n", a.question, a.answer, a.result, a.correct, a.seconds).as_str());
+        }
+
+        fs::create_dir_all(REPORTS_DIR)?;
+        let path = PathBuf::from(REPORTS_DIR).join(format!("{}-{}.csv", profile, miniquad::date::now() as u64));
+        fs::write(&path, csv)?;
+        Ok(path)
+    }
+}
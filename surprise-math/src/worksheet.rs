@@ -0,0 +1,74 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use surprise_math::{Problem, Token};
+
+const SVG_WIDTH: usize = 800;
+const SVG_ROW: usize = 60;
+const SVG_TOP: usize = 120;
+
+/// Writes the problems to print, an SVG page when the path ends in `.svg`
+/// and plain text otherwise. The answers go at the bottom, below a dashed
+/// line to fold or cut along on the SVG page, so the sheet can be checked
+/// afterwards.
+pub fn write(path: &Path, problems: &[Problem], seed: u64) -> io::Result<()> {
+    let svg = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("svg"));
+    let sheet = if svg { to_svg(problems, seed) } else { to_text(problems, seed) };
+    fs::write(path, sheet)
+}
+
+/// The problem with a blank to write in, as in `7 + ____ = 12`.
+fn blank(problem: &Problem) -> String {
+    problem.tokens.iter()
+        .map(|t| if *t == Token::Unknown { "____".to_string() } else { t.value() })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn to_text(problems: &[Problem], seed: u64) -> String {
+    let mut text = format!("Surprise math, worksheet {}\n\nName: ______________\n\n", seed);
+    for (i, problem) in problems.iter().enumerate() {
+        text.push_str(format!("{:>3}.  {}\n\n", i + 1, blank(problem)).as_str());
+    }
+    text.push_str("\nAnswers: ");
+    let answers: Vec<String> = problems.iter().enumerate().map(|(i, p)| format!("{}) {}", i + 1, p.result())).collect();
+    text.push_str(answers.join("   ").as_str());
+    text.push('\n');
+    text
+}
+
+fn to_svg(problems: &[Problem], seed: u64) -> String {
+    // two columns of problems, then the answer key below them
+    let rows = problems.len().div_ceil(2);
+    let key_top = SVG_TOP + rows * SVG_ROW + 80;
+    let height = key_top + rows * 30 + 60;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\">\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        SVG_WIDTH, height
+    );
+    svg.push_str(format!("<text x=\"40\" y=\"50\" font-size=\"28\">Surprise math, worksheet {}</text>\n", seed).as_str());
+    svg.push_str("<text x=\"40\" y=\"90\" font-size=\"20\">Name: ______________</text>\n");
+    for (i, problem) in problems.iter().enumerate() {
+        let x = 40 + (i % 2) * SVG_WIDTH / 2;
+        let y = SVG_TOP + (i / 2) * SVG_ROW + 30;
+        let line = format!("{}.  {}", i + 1, blank(problem));
+        svg.push_str(format!("<text x=\"{}\" y=\"{}\" font-size=\"28\">{}</text>\n", x, y, escape(&line)).as_str());
+    }
+
+    svg.push_str(format!("<line x1=\"40\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"gray\" stroke-dasharray=\"8 6\"/>\n", key_top - 40, SVG_WIDTH - 40, key_top - 40).as_str());
+    svg.push_str(format!("<text x=\"40\" y=\"{}\" font-size=\"18\" fill=\"gray\">Answers</text>\n", key_top - 10).as_str());
+    for (i, problem) in problems.iter().enumerate() {
+        let x = 40 + (i % 2) * SVG_WIDTH / 2;
+        let y = key_top + (i / 2) * 30 + 20;
+        let line = format!("{}.  {}", i + 1, problem.result());
+        svg.push_str(format!("<text x=\"{}\" y=\"{}\" font-size=\"18\" fill=\"gray\">{}</text>\n", x, y, escape(&line)).as_str());
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Comparisons put `<` and `>` on the sheet, which SVG reads as markup.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
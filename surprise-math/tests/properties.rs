@@ -0,0 +1,152 @@
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use surprise_math::{Generator, Kind, Model, Operation, Rational, Skill, Token, TABLES_LIMIT};
+
+const OPERATIONS: [Operation; 4] = [Operation::Plus, Operation::Sub, Operation::Mul, Operation::Div];
+const KINDS: [Kind; 7] = [Kind::Basic, Kind::Missing, Kind::ThreeTerms, Kind::Compare, Kind::Fractions, Kind::Decimals, Kind::PlaceValue];
+
+fn model(limit: i32, tables: i32) -> Model {
+    let mut model = Model::default();
+    model.limit = limit;
+    model.tables = tables;
+    model
+}
+
+proptest! {
+    #[test]
+    fn subtraction_never_goes_below_zero(seed: u64, limit in 2..=100) {
+        let (a, b) = Operation::Sub.operands(limit, 5, &mut StdRng::seed_from_u64(seed));
+        prop_assert!(Operation::Sub.apply(a, b) >= 0);
+    }
+
+    #[test]
+    fn division_is_exact(seed: u64, tables in 1..=12) {
+        let (a, b) = Operation::Div.operands(20, tables, &mut StdRng::seed_from_u64(seed));
+        prop_assert_eq!(a % b, 0);
+        prop_assert!((1..=tables).contains(&b));
+        prop_assert!((1..=TABLES_LIMIT).contains(&(a / b)));
+    }
+
+    #[test]
+    fn operands_stay_in_range(seed: u64, limit in 2..=100, tables in 1..=12) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for operation in [Operation::Plus, Operation::Sub] {
+            let (a, b) = operation.operands(limit, tables, &mut rng);
+            prop_assert!((2..=limit).contains(&a));
+            prop_assert!((1..a).contains(&b));
+        }
+        let (a, b) = Operation::Mul.operands(limit, tables, &mut rng);
+        prop_assert!((1..=TABLES_LIMIT).contains(&a));
+        prop_assert!((1..=tables).contains(&b));
+    }
+
+    #[test]
+    fn problems_accept_their_solution(
+        seed: u64,
+        operations in subsequence(OPERATIONS.to_vec(), 1..=4),
+        kinds in subsequence(KINDS.to_vec(), 1..=7),
+    ) {
+        let mut generator = Generator::new(seed);
+        let problem = generator.problem(&operations, &kinds, &Model::default());
+        prop_assert!(problem.check(&problem.result()));
+        if let Some(n) = problem.solution.number() {
+            prop_assert!(n >= Rational::integer(0));
+            prop_assert!(!problem.check(&n.checked_add(Rational::integer(1)).unwrap().to_string()));
+            // in lowest terms and as a decimal too
+            prop_assert!(problem.check(&n.to_string()));
+            if let Some(decimal) = n.to_decimal() {
+                prop_assert!(problem.check(&decimal));
+            }
+        }
+    }
+
+    #[test]
+    fn rationals_read_back_what_they_write(num in -1_000_000_000_000_i64..=1_000_000_000_000, den in 1_i64..=1 << 40) {
+        let n = Rational::new(num, den).unwrap();
+        prop_assert_eq!(Rational::parse(&n.to_string()), Some(n));
+        if let Some(decimal) = n.to_decimal() {
+            prop_assert_eq!(Rational::parse(&decimal), Some(n));
+        }
+    }
+
+    #[test]
+    fn choices_hold_one_right_answer(
+        seed: u64,
+        operations in subsequence(OPERATIONS.to_vec(), 1..=4),
+        kinds in subsequence(KINDS.to_vec(), 1..=7),
+    ) {
+        let mut generator = Generator::new(seed);
+        let problem = generator.problem(&operations, &kinds, &Model::default());
+        let choices = generator.choices(&problem, 4);
+        let answers: Vec<String> = choices.iter().map(Token::value).collect();
+
+        prop_assert_eq!(choices.len(), if problem.wants_relation() { 3 } else { 4 });
+        prop_assert_eq!(answers.iter().filter(|a| problem.check(a)).count(), 1);
+        for (i, answer) in answers.iter().enumerate() {
+            prop_assert!(!answers[i + 1..].contains(answer));
+            prop_assert!(!answer.starts_with('-'));
+        }
+    }
+
+    #[test]
+    fn generator_never_panics(
+        seed: u64,
+        operations in subsequence(OPERATIONS.to_vec(), 0..=4),
+        kinds in subsequence(KINDS.to_vec(), 0..=7),
+        limit in -10..=1000,
+        tables in -10..=100,
+    ) {
+        let mut generator = Generator::new(seed);
+        let problem = generator.problem(&operations, &kinds, &model(limit, tables));
+        prop_assert!(problem.check(&problem.result()));
+    }
+}
+
+#[test]
+fn same_seed_same_problems() {
+    let questions = |seed| {
+        let mut generator = Generator::new(seed);
+        (0..20).map(|_| generator.problem(&OPERATIONS, &KINDS, &Model::default()).question()).collect::<Vec<String>>()
+    };
+    assert_eq!(questions(42), questions(42));
+}
+
+#[test]
+fn answers_in_any_form() {
+    let three_quarters = Rational::new(3, 4);
+    for text in ["3/4", "6/8", "0.75", ".75", "0,75", " 3 / 4 "] {
+        assert_eq!(Rational::parse(text), three_quarters, "{}", text);
+    }
+    assert_eq!(Rational::parse("-7"), Some(Rational::integer(-7)));
+    assert_eq!(Rational::parse("07"), Some(Rational::integer(7)));
+    for text in ["", "-", ".", "3/0", "3/", "1.2.3", "1e3", "three"] {
+        assert_eq!(Rational::parse(text), None, "{}", text);
+    }
+    for text in ["-9223372036854775808/1", "1/-9223372036854775808", "-9223372036854775808"] {
+        assert_eq!(Rational::parse(text), None, "{}", text);
+    }
+    assert_eq!(Rational::integer(i64::MIN + 1).checked_add(Rational::integer(-1)), None);
+    assert_eq!(Rational::new(1, 3).unwrap().to_decimal(), None);
+    assert_eq!(Rational::parse("1/1099511627776").unwrap().to_decimal(), None);
+    assert_eq!(Rational::parse("1/1024").unwrap().to_decimal(), None);
+    assert_eq!(Rational::integer(i64::MAX / 2).checked_div(Rational::integer(2)).unwrap().to_decimal(), None);
+    assert_eq!(Rational::new(-5, 4).unwrap().to_decimal().as_deref(), Some("-1.25"));
+}
+
+#[test]
+fn range_waits_after_a_calm_spell() {
+    let mut model = Model::default();
+    let skill = Skill::of(3, Operation::Plus, 4);
+    // answers right on target, the range has no reason to move
+    for _ in 0..12 {
+        model.recent = 0.8;
+        model.record(skill, true, 2.0);
+    }
+    let limit = model.limit;
+    // one great answer is not enough to make it harder
+    model.recent = 0.95;
+    model.record(skill, true, 2.0);
+    assert_eq!(model.limit, limit);
+}